@@ -0,0 +1,66 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use naive_rsa::{
+    asn1::RSAPublicKey,
+    rsa::{encrypt::rsaes_pkcs1_v1_5_encrypt, gen_keypair::generate_keypair, hazmat::os2ip},
+};
+use num_bigint::BigInt;
+
+const MESSAGES: usize = 100;
+
+/// Encrypting a batch of messages to one recipient, once with a key that
+/// keeps its Montgomery parameters between calls and once with a new key
+/// per message, which redoes the setup every time. The `num_bigint` case is
+/// the RSAEP this crate used before, cloning the key into `BigInt`s and
+/// calling `modpow` for every message.
+fn bulk_encryption(c: &mut Criterion) {
+    let key = generate_keypair(2048);
+    let public_key = RSAPublicKey::from(&key);
+    let messages: Vec<Vec<u8>> = (0..MESSAGES).map(|i| vec![i as u8; 32]).collect();
+
+    let mut group = c.benchmark_group("bulk_pkcs1_v1_5_encrypt_2048");
+    group.throughput(Throughput::Elements(MESSAGES as u64));
+
+    group.bench_function("cached_key", |b| {
+        b.iter(|| {
+            for message in &messages {
+                black_box(rsaes_pkcs1_v1_5_encrypt(&public_key, message).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("fresh_key", |b| {
+        b.iter_batched(
+            || {
+                (0..MESSAGES)
+                    .map(|_| RSAPublicKey::new(key.modulus.clone(), key.public_exponent.clone()))
+                    .collect::<Vec<_>>()
+            },
+            |keys| {
+                for (key, message) in keys.iter().zip(&messages) {
+                    black_box(rsaes_pkcs1_v1_5_encrypt(key, message).unwrap());
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let padded: Vec<BigInt> = messages
+        .iter()
+        .map(|message| os2ip(&[&[0x00, 0x02], &[0xaa; 221][..], &[0x00], message].concat()))
+        .collect();
+    group.bench_function("num_bigint_modpow", |b| {
+        b.iter(|| {
+            for integer_message in &padded {
+                black_box(integer_message.modpow(
+                    &BigInt::from(public_key.public_exponent.clone()),
+                    &BigInt::from(public_key.modulus.clone()),
+                ));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bulk_encryption);
+criterion_main!(benches);
@@ -0,0 +1,525 @@
+//! Fixed-width limb arithmetic: a constant-time Montgomery modular
+//! exponentiation for secret exponents, and `FixedUint`, the integer type
+//! behind the `constant-time` feature.
+//!
+//! `num_bigint` picks its algorithms and loop bounds from the values it is
+//! given, so the time `modpow` takes depends on the exponent. Everything in
+//! here instead works on little-endian `u64` limbs whose count is fixed by
+//! the modulus, and every branch and memory access depends only on that
+//! count. The one-off setup in `MontgomeryContext::new` and the conversions
+//! to and from `BigUint` are not constant time.
+
+use std::{cmp::Ordering, hint::black_box};
+
+use num_bigint::BigUint;
+
+/// Bits per limb.
+pub const LIMB_BITS: usize = 64;
+
+/// Bits of exponent consumed per table lookup.
+const WINDOW_BITS: usize = 4;
+
+/// All ones if `a == b`, all zeros otherwise, without branching.
+///
+/// The result goes through `black_box` so the optimizer cannot see that it
+/// is a mask and turn the selects built on it back into branches.
+fn ct_eq(a: u64, b: u64) -> u64 {
+    let x = a ^ b;
+    black_box(((x | x.wrapping_neg()) >> 63).wrapping_sub(1))
+}
+
+/// `a + b + carry`, returning the low limb and the new carry.
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let wide = a as u128 + b as u128 + carry as u128;
+    (wide as u64, (wide >> LIMB_BITS) as u64)
+}
+
+/// `a - b - borrow`, returning the low limb and the new borrow (0 or 1).
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let wide = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (wide as u64, (wide >> 127) as u64)
+}
+
+/// `a + b * c + carry`, returning the low limb and the new carry.
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let wide = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (wide as u64, (wide >> LIMB_BITS) as u64)
+}
+
+/// Limbwise `if mask { a } else { b }` for an all-ones or all-zeros mask.
+fn select(mask: u64, a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x & mask) | (y & !mask))
+        .collect()
+}
+
+/// Number of limbs needed to hold `bits` bits.
+pub fn limbs_for_bits(bits: u64) -> usize {
+    (bits as usize).div_ceil(LIMB_BITS).max(1)
+}
+
+/// `value` as exactly `width` little-endian limbs.
+///
+/// Panics if `value` does not fit.
+pub fn to_limbs(value: &BigUint, width: usize) -> Vec<u64> {
+    let mut limbs = value.to_u64_digits();
+    assert!(
+        limbs.len() <= width,
+        "value does not fit in {} limbs",
+        width
+    );
+    limbs.resize(width, 0);
+    limbs
+}
+
+pub fn from_limbs(limbs: &[u64]) -> BigUint {
+    let words: Vec<u32> = limbs
+        .iter()
+        .flat_map(|limb| [*limb as u32, (*limb >> 32) as u32])
+        .collect();
+    BigUint::new(words)
+}
+
+/// Precomputed values for Montgomery arithmetic modulo an odd `n`.
+#[derive(Debug, Clone)]
+pub struct MontgomeryContext {
+    modulus: BigUint,
+    n: Vec<u64>,
+    n0_inv: u64,     // -n^-1 mod 2^64
+    r2: Vec<u64>,    // R^2 mod n, with R = 2^(64 * width)
+    r_mod: Vec<u64>, // R mod n, which is 1 in Montgomery form
+}
+
+impl MontgomeryContext {
+    /// Returns `None` unless `modulus` is odd and greater than 1.
+    pub fn new(modulus: &BigUint) -> Option<Self> {
+        if modulus <= &BigUint::from(1u64) || !modulus.bit(0) {
+            return None;
+        }
+
+        let width = limbs_for_bits(modulus.bits());
+        let n = to_limbs(modulus, width);
+
+        // Newton iteration doubles the correct low bits each round, and
+        // n0 * n0 = 1 mod 8 for odd n0 so five rounds reach 64 bits
+        let mut inverse = n[0];
+        for _ in 0..5 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inverse)));
+        }
+
+        let r = BigUint::from(1u64) << (LIMB_BITS * width);
+        let r_mod = &r % modulus;
+        let r2 = (&r_mod * &r_mod) % modulus;
+
+        Some(Self {
+            modulus: modulus.clone(),
+            n,
+            n0_inv: inverse.wrapping_neg(),
+            r2: to_limbs(&r2, width),
+            r_mod: to_limbs(&r_mod, width),
+        })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Number of limbs every value modulo `n` is stored in.
+    pub fn width(&self) -> usize {
+        self.n.len()
+    }
+
+    /// Montgomery product `a * b * R^-1 mod n` of two reduced values, using
+    /// the CIOS method with a branch-free final subtraction.
+    pub fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let width = self.width();
+        let mut t = vec![0u64; width + 2];
+
+        for &a_i in a.iter().take(width) {
+            let mut carry = 0;
+            for j in 0..width {
+                (t[j], carry) = mac(t[j], a_i, b[j], carry);
+            }
+            (t[width], carry) = adc(t[width], carry, 0);
+            t[width + 1] = carry;
+
+            let m = t[0].wrapping_mul(self.n0_inv);
+            let (_, mut carry) = mac(t[0], m, self.n[0], 0);
+            for j in 1..width {
+                (t[j - 1], carry) = mac(t[j], m, self.n[j], carry);
+            }
+            (t[width - 1], carry) = adc(t[width], carry, 0);
+            t[width] = t[width + 1] + carry;
+        }
+
+        // t < 2n here, subtract n once if t >= n
+        let mut reduced = vec![0u64; width];
+        let mut borrow = 0;
+        for j in 0..width {
+            (reduced[j], borrow) = sbb(t[j], self.n[j], borrow);
+        }
+        let (_, borrow) = sbb(t[width], 0, borrow);
+
+        // borrow is 1 exactly when t < n, in which case t is kept
+        let keep = black_box(borrow.wrapping_neg());
+        for j in 0..width {
+            reduced[j] = (t[j] & keep) | (reduced[j] & !keep);
+        }
+
+        reduced
+    }
+
+    /// `value * R mod n`, reducing `value` with `FixedUint::div_rem` so the
+    /// reduction does not depend on the value either.
+    pub fn to_montgomery(&self, value: &BigUint) -> Vec<u64> {
+        let modulus = FixedUint {
+            limbs: self.n.clone(),
+        };
+        let reduced = FixedUint::from_biguint(value).rem(&modulus);
+        self.mul(&reduced.limbs, &self.r2)
+    }
+
+    pub fn from_montgomery(&self, value: &[u64]) -> BigUint {
+        let mut one = vec![0u64; self.width()];
+        one[0] = 1;
+        from_limbs(&self.mul(value, &one))
+    }
+
+    /// Copies `table[index]` into a new vector, touching every entry so the
+    /// memory access pattern does not depend on `index`.
+    fn select_entry(&self, table: &[Vec<u64>], index: u64) -> Vec<u64> {
+        let mut selected = vec![0u64; self.width()];
+        for (i, entry) in table.iter().enumerate() {
+            let mask = ct_eq(i as u64, index);
+            for (out, limb) in selected.iter_mut().zip(entry) {
+                *out |= limb & mask;
+            }
+        }
+        selected
+    }
+
+    /// `base^exponent mod n` in time that depends only on the width of `n`
+    /// and `exponent_limbs`, using a fixed 4-bit window.
+    ///
+    /// The exponent is padded to `exponent_limbs` limbs, which must be large
+    /// enough to hold it. Callers should derive it from public information
+    /// such as the modulus size.
+    pub fn pow_with_width(
+        &self,
+        base: &BigUint,
+        exponent: &BigUint,
+        exponent_limbs: usize,
+    ) -> BigUint {
+        let base = self.to_montgomery(base);
+        let exponent = to_limbs(exponent, exponent_limbs);
+
+        self.from_montgomery(&self.pow_montgomery(&base, &exponent))
+    }
+
+    /// Fixed-window exponentiation of `base`, given and returned in
+    /// Montgomery form, by an exponent given as limbs.
+    fn pow_montgomery(&self, base: &[u64], exponent: &[u64]) -> Vec<u64> {
+        // table[i] = base^i in Montgomery form
+        let mut table = Vec::with_capacity(1 << WINDOW_BITS);
+        table.push(self.r_mod.clone());
+        for i in 1..(1 << WINDOW_BITS) {
+            let next = self.mul(&table[i - 1], base);
+            table.push(next);
+        }
+
+        let mut result = self.r_mod.clone();
+        for limb in exponent.iter().rev() {
+            for window in (0..LIMB_BITS / WINDOW_BITS).rev() {
+                for _ in 0..WINDOW_BITS {
+                    result = self.mul(&result, &result);
+                }
+
+                let digit = (limb >> (window * WINDOW_BITS)) & ((1 << WINDOW_BITS) - 1);
+                result = self.mul(&result, &self.select_entry(&table, digit));
+            }
+        }
+
+        result
+    }
+
+    /// `base^exponent mod n` for an exponent no wider than the modulus,
+    /// which covers `d` as well as the CRT exponents.
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        self.pow_with_width(base, exponent, self.width())
+    }
+
+    /// `base^exponent mod n` by plain square-and-multiply, skipping the
+    /// multiplications for zero bits. Only for public exponents.
+    pub fn pow_vartime(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let base = self.to_montgomery(base);
+
+        let mut result = self.r_mod.clone();
+        for i in (0..exponent.bits()).rev() {
+            result = self.mul(&result, &result);
+            if exponent.bit(i) {
+                result = self.mul(&result, &base);
+            }
+        }
+
+        self.from_montgomery(&result)
+    }
+}
+
+/// Constant-time `base^exponent mod modulus` for odd moduli.
+///
+/// Builds a fresh context on every call; hold on to a `MontgomeryContext`
+/// when exponentiating repeatedly with the same modulus.
+pub fn modpow_ct(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    let context = MontgomeryContext::new(modulus)?;
+    let exponent_limbs = context.width().max(limbs_for_bits(exponent.bits()));
+
+    Some(context.pow_with_width(base, exponent, exponent_limbs))
+}
+
+/// An unsigned integer held in a fixed number of limbs.
+///
+/// The width is set when a value is created, from the `BigUint` it was
+/// converted from or from the widths of the operands it was computed from,
+/// never from the value itself. Arithmetic runs in time that depends only on
+/// the widths. Predicates such as `is_zero` and `compare` reveal their
+/// answer, but not how they got there.
+#[derive(Debug, Clone)]
+pub struct FixedUint {
+    limbs: Vec<u64>,
+}
+
+impl FixedUint {
+    /// `value` in as many limbs as it needs.
+    pub fn from_biguint(value: &BigUint) -> Self {
+        Self::with_width(value, limbs_for_bits(value.bits()))
+    }
+
+    /// `value` in exactly `width` limbs. Panics if it does not fit.
+    pub fn with_width(value: &BigUint, width: usize) -> Self {
+        Self {
+            limbs: to_limbs(value, width),
+        }
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        from_limbs(&self.limbs)
+    }
+
+    pub fn width(&self) -> usize {
+        self.limbs.len()
+    }
+
+    /// The limbs zero-extended to at least `width`.
+    fn padded(&self, width: usize) -> Vec<u64> {
+        let mut limbs = self.limbs.clone();
+        limbs.resize(width.max(self.width()), 0);
+        limbs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().fold(0, |acc, limb| acc | limb) == 0
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.limbs[0] & 1 == 1
+    }
+
+    /// Number of significant bits, looking at every limb.
+    pub fn bits(&self) -> u64 {
+        let mut bits = 0;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let nonzero = !ct_eq(limb, 0);
+            let candidate = ((i + 1) * LIMB_BITS) as u64 - limb.leading_zeros() as u64;
+            bits = (candidate & nonzero) | (bits & !nonzero);
+        }
+        bits
+    }
+
+    /// Number of trailing zero bits, or `None` for zero.
+    pub fn trailing_zeros(&self) -> Option<u64> {
+        let mut zeros = 0;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let nonzero = !ct_eq(limb, 0);
+            let candidate = (i * LIMB_BITS) as u64 + limb.trailing_zeros() as u64;
+            zeros = (candidate & nonzero) | (zeros & !nonzero);
+        }
+
+        if self.is_zero() {
+            None
+        } else {
+            Some(zeros)
+        }
+    }
+
+    pub fn compare(&self, other: &Self) -> Ordering {
+        let width = self.width().max(other.width());
+        let (a, b) = (self.padded(width), other.padded(width));
+
+        let mut borrow = 0;
+        let mut difference = 0;
+        for j in 0..width {
+            let limb;
+            (limb, borrow) = sbb(a[j], b[j], borrow);
+            difference |= limb;
+        }
+
+        match (borrow, difference) {
+            (1, _) => Ordering::Less,
+            (_, 0) => Ordering::Equal,
+            _ => Ordering::Greater,
+        }
+    }
+
+    /// `self + other`, one limb wider than the wider operand.
+    pub fn add(&self, other: &Self) -> Self {
+        let width = self.width().max(other.width());
+        let (a, b) = (self.padded(width), other.padded(width));
+
+        let mut limbs = vec![0u64; width + 1];
+        let mut carry = 0;
+        for j in 0..width {
+            (limbs[j], carry) = adc(a[j], b[j], carry);
+        }
+        limbs[width] = carry;
+
+        Self { limbs }
+    }
+
+    /// `self - other` modulo `2^(64 * width)`, as wide as the wider operand.
+    pub fn sub(&self, other: &Self) -> Self {
+        let width = self.width().max(other.width());
+        let (a, b) = (self.padded(width), other.padded(width));
+
+        let mut limbs = vec![0u64; width];
+        let mut borrow = 0;
+        for j in 0..width {
+            (limbs[j], borrow) = sbb(a[j], b[j], borrow);
+        }
+
+        Self { limbs }
+    }
+
+    /// `self * other`, as wide as both operands together.
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut limbs = vec![0u64; self.width() + other.width()];
+        for (i, &a_i) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b_j) in other.limbs.iter().enumerate() {
+                (limbs[i + j], carry) = mac(limbs[i + j], a_i, b_j, carry);
+            }
+            limbs[i + other.width()] = carry;
+        }
+
+        Self { limbs }
+    }
+
+    /// Quotient and remainder by binary long division, one bit of `self` at
+    /// a time. The quotient is as wide as `self` and the remainder as wide as
+    /// `divisor`. Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        // One spare limb, since the remainder is doubled before subtracting
+        let width = divisor.width() + 1;
+        let d = divisor.padded(width);
+        let mut quotient = vec![0u64; self.width()];
+        let mut remainder = vec![0u64; width];
+
+        for i in (0..self.width() * LIMB_BITS).rev() {
+            // remainder = 2 * remainder + bit i of self
+            let mut carry = (self.limbs[i / LIMB_BITS] >> (i % LIMB_BITS)) & 1;
+            for limb in remainder.iter_mut() {
+                let top = *limb >> (LIMB_BITS - 1);
+                *limb = (*limb << 1) | carry;
+                carry = top;
+            }
+
+            let mut difference = vec![0u64; width];
+            let mut borrow = 0;
+            for j in 0..width {
+                (difference[j], borrow) = sbb(remainder[j], d[j], borrow);
+            }
+
+            // No borrow means remainder >= divisor, so keep the difference
+            let keep_difference = black_box(borrow.wrapping_sub(1));
+            remainder = select(keep_difference, &difference, &remainder);
+            quotient[i / LIMB_BITS] |= (keep_difference & 1) << (i % LIMB_BITS);
+        }
+
+        remainder.truncate(divisor.width());
+        (Self { limbs: quotient }, Self { limbs: remainder })
+    }
+
+    pub fn rem(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus).1
+    }
+
+    /// `self * 2^bits`, widened by as many limbs as `bits` needs.
+    pub fn shl(&self, bits: usize) -> Self {
+        let (limb_shift, bit_shift) = (bits / LIMB_BITS, bits % LIMB_BITS);
+        let mut limbs = vec![0u64; self.width() + limb_shift + 1];
+
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            limbs[i + limb_shift] |= limb << bit_shift;
+            if bit_shift > 0 {
+                limbs[i + limb_shift + 1] = limb >> (LIMB_BITS - bit_shift);
+            }
+        }
+
+        Self { limbs }
+    }
+
+    /// `self / 2^bits`, keeping the width.
+    pub fn shr(&self, bits: usize) -> Self {
+        let (limb_shift, bit_shift) = (bits / LIMB_BITS, bits % LIMB_BITS);
+        let mut limbs = vec![0u64; self.width()];
+
+        for i in limb_shift..self.width() {
+            limbs[i - limb_shift] |= self.limbs[i] >> bit_shift;
+            if bit_shift > 0 && i > limb_shift {
+                limbs[i - limb_shift - 1] |= self.limbs[i] << (LIMB_BITS - bit_shift);
+            }
+        }
+
+        Self { limbs }
+    }
+
+    /// `self^exponent mod modulus`, as wide as `modulus`.
+    ///
+    /// Odd moduli use Montgomery multiplication. Even moduli fall back to a
+    /// square-and-always-multiply ladder over every bit of `exponent`.
+    pub fn mod_pow(&self, exponent: &Self, modulus: &Self) -> Self {
+        let base = self.rem(modulus);
+
+        let result = match MontgomeryContext::new(&modulus.to_biguint()) {
+            Some(context) => {
+                let base = context.mul(&base.limbs[..context.width()], &context.r2);
+                let result = context.pow_montgomery(&base, &exponent.limbs);
+                let mut one = vec![0u64; context.width()];
+                one[0] = 1;
+                context.mul(&result, &one)
+            }
+            None => {
+                let mut result = Self::with_width(&BigUint::from(1u64), 1).rem(modulus);
+                for i in (0..exponent.width() * LIMB_BITS).rev() {
+                    result = result.mul(&result).rem(modulus);
+                    let product = result.mul(&base).rem(modulus);
+                    let bit = (exponent.limbs[i / LIMB_BITS] >> (i % LIMB_BITS)) & 1;
+                    result.limbs =
+                        select(black_box(bit.wrapping_neg()), &product.limbs, &result.limbs);
+                }
+                result.limbs
+            }
+        };
+
+        Self { limbs: result }.resized(modulus.width())
+    }
+
+    /// Zero-extends or truncates to `width` limbs, which must not drop any
+    /// nonzero limb.
+    fn resized(mut self, width: usize) -> Self {
+        self.limbs.resize(width, 0);
+        self
+    }
+}
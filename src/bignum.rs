@@ -0,0 +1,186 @@
+//! The unsigned integer operations `math`, `prime` and `rsa::primitives`
+//! are written against, so the integer type underneath can be swapped.
+//!
+//! `Backend` is `num_bigint::BigUint` by default, which is fast but takes
+//! time that depends on the values involved. Building with the
+//! `constant-time` feature makes it `arith::FixedUint` instead, whose
+//! arithmetic only depends on operand widths. Private-key exponentiation
+//! goes through `arith::MontgomeryContext` with either backend.
+//!
+//! The public interfaces keep taking and returning `BigUint` and convert at
+//! the boundary. Signed helpers such as `math::extended_gcd` stay on
+//! `num_bigint::BigInt`.
+
+use std::{cmp::Ordering, fmt::Debug};
+
+use num_bigint::BigUint;
+
+use crate::arith::FixedUint;
+
+#[cfg(not(feature = "constant-time"))]
+pub type Backend = BigUint;
+
+#[cfg(feature = "constant-time")]
+pub type Backend = FixedUint;
+
+pub trait BigNum: Clone + Debug {
+    fn from_biguint(value: &BigUint) -> Self;
+    fn to_biguint(&self) -> BigUint;
+
+    fn is_zero(&self) -> bool;
+    fn is_odd(&self) -> bool;
+    fn bits(&self) -> u64;
+    /// `None` for zero.
+    fn trailing_zeros(&self) -> Option<u64>;
+    fn compare(&self, other: &Self) -> Ordering;
+
+    fn add(&self, other: &Self) -> Self;
+    /// `other` must not be greater than `self`.
+    fn sub(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+    /// Panics if `divisor` is zero.
+    fn div_rem(&self, divisor: &Self) -> (Self, Self);
+    fn shl(&self, bits: usize) -> Self;
+    fn shr(&self, bits: usize) -> Self;
+    fn mod_pow(&self, exponent: &Self, modulus: &Self) -> Self;
+
+    fn from_u64(value: u64) -> Self {
+        Self::from_biguint(&BigUint::from(value))
+    }
+
+    fn equals(&self, other: &Self) -> bool {
+        self.compare(other) == Ordering::Equal
+    }
+
+    fn rem(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus).1
+    }
+
+    fn mod_mul(&self, other: &Self, modulus: &Self) -> Self {
+        self.mul(other).rem(modulus)
+    }
+
+    /// `self - other mod modulus` for any `self` and `other`.
+    fn mod_sub(&self, other: &Self, modulus: &Self) -> Self {
+        self.rem(modulus)
+            .add(modulus)
+            .sub(&other.rem(modulus))
+            .rem(modulus)
+    }
+}
+
+impl BigNum for BigUint {
+    fn from_biguint(value: &BigUint) -> Self {
+        value.clone()
+    }
+
+    fn to_biguint(&self) -> BigUint {
+        self.clone()
+    }
+
+    fn is_zero(&self) -> bool {
+        self == &BigUint::from(0u64)
+    }
+
+    fn is_odd(&self) -> bool {
+        self.bit(0)
+    }
+
+    fn bits(&self) -> u64 {
+        BigUint::bits(self)
+    }
+
+    fn trailing_zeros(&self) -> Option<u64> {
+        BigUint::trailing_zeros(self)
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self - other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self * other
+    }
+
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        (self / divisor, self % divisor)
+    }
+
+    fn shl(&self, bits: usize) -> Self {
+        self << bits
+    }
+
+    fn shr(&self, bits: usize) -> Self {
+        self >> bits
+    }
+
+    fn mod_pow(&self, exponent: &Self, modulus: &Self) -> Self {
+        self.modpow(exponent, modulus)
+    }
+}
+
+impl BigNum for FixedUint {
+    fn from_biguint(value: &BigUint) -> Self {
+        FixedUint::from_biguint(value)
+    }
+
+    fn to_biguint(&self) -> BigUint {
+        FixedUint::to_biguint(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        FixedUint::is_zero(self)
+    }
+
+    fn is_odd(&self) -> bool {
+        FixedUint::is_odd(self)
+    }
+
+    fn bits(&self) -> u64 {
+        FixedUint::bits(self)
+    }
+
+    fn trailing_zeros(&self) -> Option<u64> {
+        FixedUint::trailing_zeros(self)
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        FixedUint::compare(self, other)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        FixedUint::add(self, other)
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        FixedUint::sub(self, other)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        FixedUint::mul(self, other)
+    }
+
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        FixedUint::div_rem(self, divisor)
+    }
+
+    fn shl(&self, bits: usize) -> Self {
+        FixedUint::shl(self, bits)
+    }
+
+    fn shr(&self, bits: usize) -> Self {
+        FixedUint::shr(self, bits)
+    }
+
+    fn mod_pow(&self, exponent: &Self, modulus: &Self) -> Self {
+        FixedUint::mod_pow(self, exponent, modulus)
+    }
+}
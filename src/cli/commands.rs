@@ -0,0 +1,223 @@
+//! The operations behind both the subcommands and the interactive dialogs.
+//! Each reads its inputs, does one thing and writes its output, returning a
+//! message for the user on failure.
+
+use std::fs;
+
+use naive_rsa::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    container::{
+        self,
+        armor::{armor, dearmor, is_armored, ArmorWriter},
+        is_container, Scheme,
+    },
+    format::{self, Key, KeyFormat},
+    hybrid,
+    rsa::{
+        decrypt::{rsaes_oaep_decrypt, rsaes_pkcs1_v1_5_decrypt},
+        encrypt::{rsaes_oaep_encrypt, rsaes_pkcs1_v1_5_encrypt},
+        gen_keypair::generate_multiprime_keypair,
+        sign::rsassa_pkcs1_v1_5_sign,
+        verify::rsassa_pkcs1_v1_5_verify,
+    },
+};
+
+use super::{
+    inspect::KeyReport,
+    io::{create_output, decode_data, encode_data, open_input, read_input, write_output},
+    keys::{load_key, read_key},
+    DataEncoding, Framing,
+};
+
+pub fn save_key(path: &str, key: &Key, format: KeyFormat) -> Result<(), String> {
+    write_output(path, &format::save_key(key, format)?)
+}
+
+pub fn genkey(bits: u32, primes: usize, outform: KeyFormat, out: &str) -> Result<(), String> {
+    let key = generate_multiprime_keypair(bits, primes)?;
+    save_key(out, &key.into(), outform)
+}
+
+pub fn pubkey(
+    input: &str,
+    inform: Option<KeyFormat>,
+    outform: KeyFormat,
+    out: &str,
+) -> Result<(), String> {
+    let key = load_key(input, inform)?;
+    save_key(out, &key.public_key().into(), outform)
+}
+
+/// Encrypts to a public key, or to the public half of a private key.
+pub fn encrypt(
+    key: &str,
+    inform: Option<KeyFormat>,
+    input: &str,
+    out: &str,
+    encoding: DataEncoding,
+    scheme: Scheme,
+    framing: Framing,
+) -> Result<(), String> {
+    let recipient = load_key(key, inform)?.public_key();
+    let message = read_input(input)?;
+
+    let output = encrypt_message(&recipient, &message, encoding, scheme, framing)?;
+    write_output(out, &output)
+}
+
+/// Encrypts and frames `message` the way `encrypt` writes it.
+pub fn encrypt_message(
+    recipient: &RSAPublicKey,
+    message: &[u8],
+    encoding: DataEncoding,
+    scheme: Scheme,
+    framing: Framing,
+) -> Result<Vec<u8>, String> {
+    let output = match framing {
+        Framing::Bare => {
+            let ciphertext = match scheme {
+                Scheme::Oaep => rsaes_oaep_encrypt(recipient, message, &[])?,
+                _ => rsaes_pkcs1_v1_5_encrypt(recipient, message)?,
+            };
+            encode_data(&ciphertext, encoding)
+        }
+        Framing::Container => {
+            encode_data(&container::encrypt(recipient, scheme, message)?, encoding)
+        }
+        Framing::Armored => armor(&container::encrypt(recipient, scheme, message)?).into_bytes(),
+    };
+    Ok(output)
+}
+
+/// Containers and armor are detected, `scheme` only applies to bare
+/// ciphertexts.
+pub fn decrypt(
+    key: &str,
+    inform: Option<KeyFormat>,
+    input: &str,
+    out: &str,
+    encoding: DataEncoding,
+    scheme: Scheme,
+) -> Result<(), String> {
+    let key = load_key(key, inform)?.into_private_key()?;
+    let plaintext = decrypt_message(&key, &read_input(input)?, encoding, scheme)?;
+    write_output(out, &plaintext)
+}
+
+/// Decrypts whatever `encrypt_message` produced, detecting armor and
+/// containers. `scheme` only applies to bare ciphertexts.
+pub fn decrypt_message(
+    key: &RSAPrivateKey,
+    input: &[u8],
+    encoding: DataEncoding,
+    scheme: Scheme,
+) -> Result<Vec<u8>, String> {
+    let ciphertext = if is_armored(input) {
+        dearmor(&String::from_utf8_lossy(input))?
+    } else {
+        decode_data(input, encoding)?
+    };
+
+    let plaintext = if is_container(&ciphertext) {
+        container::decrypt(key, &ciphertext)?.1
+    } else {
+        match scheme {
+            Scheme::Oaep => rsaes_oaep_decrypt(key, &ciphertext, &[])?,
+            _ => rsaes_pkcs1_v1_5_decrypt(key, &ciphertext)?,
+        }
+    };
+    Ok(plaintext)
+}
+
+/// Streams `input` through `hybrid::seal_to_recipients`, so it can be of any
+/// size.
+pub fn seal(
+    keys: &[String],
+    inform: Option<KeyFormat>,
+    input: &str,
+    out: &str,
+    armored: bool,
+) -> Result<(), String> {
+    let recipients = keys
+        .iter()
+        .map(|key| load_key(key, inform).map(|key| key.public_key()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (input, output) = (open_input(input)?, create_output(out)?);
+    if !armored {
+        return hybrid::seal_to_recipients(&recipients, input, output);
+    }
+
+    let mut writer =
+        ArmorWriter::new(output).map_err(|e| format!("failed to write output: {}", e))?;
+    hybrid::seal_to_recipients(&recipients, input, &mut writer)?;
+    writer
+        .finish()
+        .map(|_| ())
+        .map_err(|e| format!("failed to write output: {}", e))
+}
+
+/// Plaintext goes to a file next to `out` that replaces it only once every
+/// chunk has been authenticated, and is removed if the open fails. Plaintext
+/// already written to stdout cannot be taken back.
+pub fn open(key: &str, inform: Option<KeyFormat>, input: &str, out: &str) -> Result<(), String> {
+    let key = load_key(key, inform)?.into_private_key()?;
+    let input = open_input(input)?;
+    if out == "-" {
+        return hybrid::open(&key, input, create_output(out)?);
+    }
+
+    let partial = format!("{}.partial", out);
+    match create_output(&partial).and_then(|output| hybrid::open(&key, input, output)) {
+        Ok(()) => fs::rename(&partial, out).map_err(|e| format!("failed to write {}: {}", out, e)),
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+pub fn sign(
+    key: &str,
+    inform: Option<KeyFormat>,
+    input: &str,
+    out: &str,
+    encoding: DataEncoding,
+) -> Result<(), String> {
+    let key = load_key(key, inform)?.into_private_key()?;
+    let signature = rsassa_pkcs1_v1_5_sign(&key, &read_input(input)?)?;
+    write_output(out, &encode_data(&signature, encoding))
+}
+
+pub fn verify(
+    key: &str,
+    inform: Option<KeyFormat>,
+    input: &str,
+    signature: &str,
+    encoding: DataEncoding,
+) -> Result<(), String> {
+    let signer = load_key(key, inform)?.public_key();
+    let signature = decode_data(&read_input(signature)?, encoding)?;
+    rsassa_pkcs1_v1_5_verify(&signer, &read_input(input)?, &signature)
+}
+
+pub fn convert(
+    input: &str,
+    inform: Option<KeyFormat>,
+    outform: KeyFormat,
+    out: &str,
+) -> Result<(), String> {
+    save_key(out, &load_key(input, inform)?, outform)
+}
+
+pub fn inspect(input: &str, inform: Option<KeyFormat>, json: bool) -> Result<(), String> {
+    let (key, format) = read_key(input, inform)?;
+
+    let report = KeyReport::new(&key, format);
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report);
+    }
+
+    Ok(())
+}
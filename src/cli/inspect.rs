@@ -0,0 +1,155 @@
+//! The report printed by `inspect`, either as an `openssl rsa -text` style
+//! dump or as JSON.
+
+use std::fmt;
+
+use naive_rsa::{
+    asn1::RSAPublicKey,
+    fingerprint::format_hex,
+    format::{Key, KeyFormat},
+    rsa::validate::{PrivateKeyCheck, PublicKeyCheck, ValidationReport},
+};
+use num_bigint::BigUint;
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyReport {
+    pub format: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub modulus_bits: u64,
+    /// Big-endian hex without a sign byte.
+    pub modulus: String,
+    /// Decimal, since it need not fit in a JSON number.
+    pub public_exponent: String,
+    #[serde(skip)]
+    exponent: BigUint,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prime_count: Option<usize>,
+    pub fingerprints: Fingerprints,
+    pub validation: Validation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprints {
+    /// SHA-256 of the SubjectPublicKeyInfo DER, colon-separated hex, as
+    /// containers name their recipients.
+    pub sha256: String,
+    /// `ssh-keygen -l` style SHA-256 of the OpenSSH public key blob.
+    pub openssh: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Validation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<ValidationReport<PrivateKeyCheck>>,
+    pub public_key: ValidationReport<PublicKeyCheck>,
+}
+
+impl Validation {
+    fn findings(&self) -> Vec<String> {
+        let private_failures = self.private_key.iter().flat_map(|report| &report.failures);
+        private_failures
+            .map(|f| f.to_string())
+            .chain(self.public_key.failures.iter().map(|f| f.to_string()))
+            .collect()
+    }
+}
+
+impl KeyReport {
+    /// Describes `key`, which was read in `format`.
+    pub fn new(key: &Key, format: KeyFormat) -> Self {
+        let public_key = key.public_key();
+        let (kind, prime_count, private_validation) = match key {
+            Key::Private(key) => ("private", Some(key.primes().len()), Some(key.validate())),
+            Key::Public(_) => ("public", None, None),
+        };
+        Self {
+            format: format.to_string(),
+            kind,
+            modulus_bits: public_key.modulus.bits(),
+            modulus: public_key.modulus.to_str_radix(16),
+            public_exponent: public_key.public_exponent.to_string(),
+            exponent: public_key.public_exponent.clone(),
+            prime_count,
+            fingerprints: Fingerprints::new(&public_key),
+            validation: Validation {
+                private_key: private_validation,
+                public_key: public_key.validate(),
+            },
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize key report")
+    }
+}
+
+impl Fingerprints {
+    fn new(key: &RSAPublicKey) -> Self {
+        Self {
+            sha256: format_hex(&key.fingerprint_sha256()),
+            openssh: key.openssh_fingerprint_sha256(),
+        }
+    }
+}
+
+/// Writes `hex` the way OpenSSL does, 15 colon-separated bytes per line with
+/// a leading zero byte when the top bit is set.
+fn write_hex_dump(f: &mut fmt::Formatter<'_>, hex: &str) -> fmt::Result {
+    let mut hex = if hex.len() % 2 == 1 {
+        format!("0{}", hex)
+    } else {
+        hex.to_string()
+    };
+    if hex.as_bytes()[0] >= b'8' {
+        hex.insert_str(0, "00");
+    }
+
+    let bytes: Vec<&str> = (0..hex.len()).step_by(2).map(|i| &hex[i..i + 2]).collect();
+    let lines: Vec<String> = bytes.chunks(15).map(|line| line.join(":")).collect();
+    writeln!(f, "    {}", lines.join(":\n    "))
+}
+
+impl fmt::Display for KeyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let exponent = format!("{} (0x{:x})", self.exponent, self.exponent);
+
+        match self.prime_count {
+            Some(prime_count) => {
+                writeln!(
+                    f,
+                    "Private-Key: ({} bit, {} primes)",
+                    self.modulus_bits, prime_count
+                )?;
+                writeln!(f, "modulus:")?;
+                write_hex_dump(f, &self.modulus)?;
+                writeln!(f, "publicExponent: {}", exponent)?;
+            }
+            None => {
+                writeln!(f, "Public-Key: ({} bit)", self.modulus_bits)?;
+                writeln!(f, "Modulus:")?;
+                write_hex_dump(f, &self.modulus)?;
+                writeln!(f, "Exponent: {}", exponent)?;
+            }
+        }
+
+        writeln!(f, "Format: {}", self.format)?;
+        writeln!(f, "SHA-256 fingerprint: {}", self.fingerprints.sha256)?;
+        writeln!(f, "OpenSSH fingerprint: {}", self.fingerprints.openssh)?;
+
+        let findings = self.validation.findings();
+        if findings.is_empty() {
+            writeln!(f, "Validation: all checks passed")
+        } else {
+            writeln!(f, "Validation: {} problem(s) found", findings.len())?;
+            for finding in findings {
+                writeln!(f, "  - {}", finding)?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,647 @@
+//! The `--interactive` front end, prompting for each parameter before
+//! handing over to the same operations as the subcommands.
+
+use std::{io::Write, path::PathBuf, thread, time::Duration};
+
+use naive_rsa::{
+    asn1::RSAPublicKey,
+    container::Scheme,
+    fingerprint::format_hex,
+    format::{Key, KeyFormat},
+    rsa::{
+        gen_keypair::{generate_multiprime_keypair, MAX_PRIME_COUNT},
+        sign::rsassa_pkcs1_v1_5_sign,
+        verify::rsassa_pkcs1_v1_5_verify,
+    },
+};
+
+use super::{
+    commands::{decrypt_message, encrypt_message, save_key},
+    inspect::KeyReport,
+    io::{decode_data, encode_data, read_input, write_output},
+    keys::{is_encrypted, parse_key},
+    DataEncoding, Framing,
+};
+
+pub fn run() {
+    let operations = [
+        "Exit",
+        "Generate Keypair",
+        "Encrypt Message",
+        "Decrypt Message",
+        "Sign File",
+        "Verify Signature",
+        "Inspect Key",
+    ];
+
+    let selection = select("Available Operations", "Select an operation", &operations);
+
+    match selection {
+        1 => create_keypair_dialog(),
+        2 => encrypt_message_dialog(),
+        3 => decrypt_message_dialog(),
+        4 => sign_file_dialog(),
+        5 => verify_signature_dialog(),
+        6 => inspect_key_dialog(),
+        _ => {}
+    }
+}
+
+fn create_keypair_dialog() {
+    let key_sizes = ["512", "1024", "2048", "3072", "4096", "8192"];
+    let selection = select(
+        "Available Keysizes",
+        "Select a key size [2048 min. recommended]",
+        &key_sizes,
+    );
+    let key_size = key_sizes[selection].parse::<u32>().unwrap();
+
+    let prime_counts: Vec<String> = (2..=MAX_PRIME_COUNT).map(|i| i.to_string()).collect();
+    let prime_count_names: Vec<&str> = prime_counts.iter().map(String::as_str).collect();
+    let selection = select(
+        "Available Prime Counts",
+        "Select the number of primes [2 for maximum compatibility]",
+        &prime_count_names,
+    );
+    let prime_count = prime_counts[selection].parse::<usize>().unwrap();
+
+    let output_encodings = ["PEM", "DER"];
+    let output_encoding = output_encodings[select(
+        "Available Encodings",
+        "Select an encoding",
+        &output_encodings,
+    )];
+
+    let export_public_key = confirm("Export PKCS #1 RSAPublicKey to separate file?");
+
+    let default = format!("./private-key.{}", output_encoding.to_lowercase());
+    let private_key_output_location = prompt_destination("the RSAPrivateKey", Some(&default))
+        .expect("a default destination is given");
+
+    let mut public_key_output_location = PathBuf::new();
+    if export_public_key {
+        let default = format!("./public-key.{}", output_encoding.to_lowercase());
+        public_key_output_location = loop {
+            let location = prompt_destination("the RSAPublicKey", Some(&default))
+                .expect("a default destination is given");
+            if location == private_key_output_location {
+                println!("\u{001b}[31;1mCan't output two keys to the same location\u{001b}[0m");
+            } else {
+                break location;
+            }
+        };
+    }
+
+    println!("\n\n\u{001b}[1mHere are the details of your new RSA key:\n\u{001b}[0m");
+    println!("Key size: \t\t\t{}-bit", key_size);
+    println!("Number of primes: \t\t{}", prime_count);
+    println!("Output encoding: \t\t{}", output_encoding);
+    println!("Export public key: \t\t{}", export_public_key);
+    println!(
+        "Private key output location: \t{}",
+        private_key_output_location.to_string_lossy()
+    );
+    if export_public_key {
+        println!(
+            "Public key output location: \t{}",
+            public_key_output_location.to_string_lossy()
+        );
+    }
+
+    if !confirm("Proceed with key generation?") {
+        println!("\u{001b}[31;1mAborting key generation\u{001b}[0m");
+        return;
+    }
+
+    println!("\u{001b}[32;1mProceeding with key generation\u{001b}[0m");
+
+    let handle = thread::spawn(move || {
+        let private_key = generate_multiprime_keypair(key_size, prime_count)
+            .expect("Invalid key generation parameters");
+        let public_key = RSAPublicKey::from(&private_key);
+
+        (private_key, public_key)
+    });
+
+    println!("\n\u{001b}[32;1mGenerating keys\u{001b}[0m");
+    let mut char = "|";
+    loop {
+        thread::sleep(Duration::from_millis(100));
+        print!("\r~ Computing primes [\u{001b}[32;1m{}\u{001b}[0m]", char);
+        let _unused = std::io::stdout().flush();
+        if char == "|" {
+            char = "/";
+        } else if char == "/" {
+            char = "-";
+        } else if char == "-" {
+            char = "\\";
+        } else if char == "\\" {
+            char = "|";
+        }
+
+        if handle.is_finished() {
+            break;
+        }
+    }
+
+    let (private_key, public_key) = handle
+        .join()
+        .expect("Something went wrong generating the keys...");
+
+    println!("\r~ Computing primes [\u{001b}[32;1mX\u{001b}[0m]");
+    println!("~ Computing carmichael totient of the primes [\u{001b}[32;1mX\u{001b}[0m]");
+    println!("~ Computing e [\u{001b}[32;1mX\u{001b}[0m]");
+    println!("~ Computing d [\u{001b}[32;1mX\u{001b}[0m]");
+
+    // Saving consumes the keys
+    let fingerprints = describe_fingerprints(&public_key);
+
+    let output_format = if output_encoding == "PEM" {
+        KeyFormat::Pkcs1Pem
+    } else {
+        KeyFormat::Pkcs1Der
+    };
+    save_key(
+        &private_key_output_location.to_string_lossy(),
+        &private_key.into(),
+        output_format,
+    )
+    .expect("Failed to write files");
+    if export_public_key {
+        save_key(
+            &public_key_output_location.to_string_lossy(),
+            &public_key.into(),
+            output_format,
+        )
+        .expect("Failed to write files");
+    }
+
+    println!("~ Writing to files [\u{001b}[32;1mX\u{001b}[0m]");
+    println!("{}", fingerprints);
+}
+
+fn encrypt_message_dialog() {
+    let scheme = select_scheme("Select an encryption scheme");
+
+    let (public_key_file_location, key_format, key) = match prompt_key(
+        "the intended recipient's public key",
+        "./recipient-public-key.pem",
+    ) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            println!("\u{001b}[31;1mFailed to load key: {}\u{001b}[0m", e);
+            return;
+        }
+    };
+    let recipient = key.public_key();
+
+    // RSAES-PKCS1-v1_5 needs 11 bytes of the k byte modulus for padding,
+    // RSAES-OAEP with SHA-256 needs 66
+    let modulus_length = recipient.modulus.to_bytes_be().len();
+    let max_message_size = match scheme {
+        Scheme::Oaep => modulus_length.saturating_sub(2 * 32 + 2),
+        _ => modulus_length.saturating_sub(11),
+    };
+    if max_message_size == 0 {
+        println!("\u{001b}[31;1mThe recipient's modulus is too short to encrypt with\u{001b}[0m");
+        return;
+    }
+
+    let message = loop {
+        print!(
+            "\n\u{001b}[33;1mEnter a message ({} bytes total)> \u{001b}[0m",
+            max_message_size
+        );
+        let _unused = std::io::stdout().flush();
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap();
+        input = input.trim().to_string();
+        if input.len() > max_message_size {
+            println!(
+                "\u{001b}[31;1mMessage is {} bytes, the limit is {}\u{001b}[0m",
+                input.len(),
+                max_message_size
+            );
+        } else if !input.is_empty() {
+            break input;
+        }
+    };
+
+    let (ciphertext_encoding, data_encoding) = select_data_encoding("the ciphertext");
+    // Text encodings can be printed, raw ciphertext has to go to a file
+    let default = (data_encoding == DataEncoding::Binary).then_some("./ciphertext.bin");
+    let ciphertext_output_location = prompt_destination("the ciphertext", default);
+
+    println!("\n\n\u{001b}[1mHere are the details of the message to encrypt:\n\u{001b}[0m");
+    println!("Encryption scheme: \t\t{}", scheme);
+    println!("Recipient's key format: \t{}", key_format);
+    println!(
+        "Public key file location: \t{}",
+        public_key_file_location.to_string_lossy()
+    );
+    println!("Ciphertext encoding: \t\t{}", ciphertext_encoding);
+    print_destination("Ciphertext", &ciphertext_output_location);
+    println!("Message: \n\n{}", message);
+
+    if !confirm("Proceed with encryption?") {
+        println!("\u{001b}[31;1mAborting encryption\u{001b}[0m");
+        return;
+    }
+
+    println!("\u{001b}[32;1mProceeding with encryption\u{001b}[0m");
+
+    println!("\n\u{001b}[32;1mEncrypting message\u{001b}[0m");
+    let encoded = match encrypt_message(
+        &recipient,
+        message.as_bytes(),
+        data_encoding,
+        scheme,
+        Framing::Bare,
+    ) {
+        Ok(encoded) => encoded,
+        Err(e) => {
+            println!("\u{001b}[31;1mEncryption failed: {}\u{001b}[0m", e);
+            return;
+        }
+    };
+    println!("~ Encrypting with {} [\u{001b}[32;1mX\u{001b}[0m]", scheme);
+
+    match ciphertext_output_location {
+        Some(location) => {
+            write_output(&location.to_string_lossy(), &encoded)
+                .expect("Failed to write ciphertext");
+            println!("~ Writing to file [\u{001b}[32;1mX\u{001b}[0m]");
+        }
+        None => println!("\n{}", String::from_utf8_lossy(&encoded).trim_end()),
+    }
+}
+
+fn decrypt_message_dialog() {
+    let (private_key_file_location, _, key) =
+        match prompt_key("your private key", "./private-key.pem") {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                println!("\u{001b}[31;1mFailed to load key: {}\u{001b}[0m", e);
+                return;
+            }
+        };
+    let private_key = match key.into_private_key() {
+        Ok(private_key) => private_key,
+        Err(e) => {
+            println!("\u{001b}[31;1m{}\u{001b}[0m", e);
+            return;
+        }
+    };
+
+    let ciphertext_file_location = prompt_existing_file("the ciphertext", "./ciphertext.bin");
+    let (ciphertext_encoding, data_encoding) = select_data_encoding("the ciphertext");
+    // Containers and armor name their own scheme
+    let scheme = select_scheme("Select the scheme of a bare ciphertext");
+    let plaintext_output_location = prompt_destination("the plaintext", None);
+
+    println!("\n\n\u{001b}[1mHere are the details of the message to decrypt:\n\u{001b}[0m");
+    println!(
+        "Encryption scheme: \t\t{} unless the ciphertext is a container",
+        scheme
+    );
+    println!(
+        "Private key file location: \t{}",
+        private_key_file_location.to_string_lossy()
+    );
+    println!(
+        "Ciphertext file location: \t{}",
+        ciphertext_file_location.to_string_lossy()
+    );
+    println!("Ciphertext encoding: \t\t{}", ciphertext_encoding);
+    print_destination("Plaintext", &plaintext_output_location);
+
+    if !confirm("Proceed with decryption?") {
+        println!("\u{001b}[31;1mAborting decryption\u{001b}[0m");
+        return;
+    }
+
+    println!("\u{001b}[32;1mProceeding with decryption\u{001b}[0m");
+
+    println!("\n\u{001b}[32;1mDecrypting message\u{001b}[0m");
+    let plaintext = read_input(&ciphertext_file_location.to_string_lossy())
+        .and_then(|input| decrypt_message(&private_key, &input, data_encoding, scheme));
+    let plaintext = match plaintext {
+        Ok(plaintext) => plaintext,
+        Err(e) => {
+            println!("\u{001b}[31;1mDecryption failed: {}\u{001b}[0m", e);
+            return;
+        }
+    };
+    println!("~ Decrypting [\u{001b}[32;1mX\u{001b}[0m]");
+
+    match plaintext_output_location {
+        Some(location) => {
+            write_output(&location.to_string_lossy(), &plaintext)
+                .expect("Failed to write plaintext");
+            println!("~ Writing to file [\u{001b}[32;1mX\u{001b}[0m]");
+        }
+        None => match std::str::from_utf8(&plaintext) {
+            Ok(message) => println!("\nMessage: \n\n{}", message),
+            Err(_) => println!(
+                "\nMessage (hex): \n\n{}",
+                String::from_utf8_lossy(&encode_data(&plaintext, DataEncoding::Hex)).trim_end()
+            ),
+        },
+    }
+}
+
+fn sign_file_dialog() {
+    let (private_key_file_location, _, key) =
+        match prompt_key("your private key", "./private-key.pem") {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                println!("\u{001b}[31;1mFailed to load key: {}\u{001b}[0m", e);
+                return;
+            }
+        };
+    let private_key = match key.into_private_key() {
+        Ok(private_key) => private_key,
+        Err(e) => {
+            println!("\u{001b}[31;1m{}\u{001b}[0m", e);
+            return;
+        }
+    };
+
+    let file_location = prompt_existing_file("the file to sign", "./message.txt");
+    let (signature_encoding, data_encoding) = select_data_encoding("the signature");
+    let default = (data_encoding == DataEncoding::Binary).then_some("./signature.bin");
+    let signature_output_location = prompt_destination("the signature", default);
+
+    println!("\n\n\u{001b}[1mHere are the details of the file to sign:\n\u{001b}[0m");
+    println!("Signature scheme: \t\tRSASSA-PKCS1-V1_5 with SHA-256");
+    println!(
+        "Private key file location: \t{}",
+        private_key_file_location.to_string_lossy()
+    );
+    println!("File location: \t\t\t{}", file_location.to_string_lossy());
+    println!("Signature encoding: \t\t{}", signature_encoding);
+    print_destination("Signature", &signature_output_location);
+
+    if !confirm("Proceed with signing?") {
+        println!("\u{001b}[31;1mAborting signing\u{001b}[0m");
+        return;
+    }
+
+    println!("\u{001b}[32;1mProceeding with signing\u{001b}[0m");
+
+    println!("\n\u{001b}[32;1mSigning file\u{001b}[0m");
+    let signature = read_input(&file_location.to_string_lossy())
+        .and_then(|message| Ok(rsassa_pkcs1_v1_5_sign(&private_key, &message)?));
+    let signature = match signature {
+        Ok(signature) => signature,
+        Err(e) => {
+            println!("\u{001b}[31;1mSigning failed: {}\u{001b}[0m", e);
+            return;
+        }
+    };
+    println!("~ Signing with RSASSA-PKCS1-V1_5 [\u{001b}[32;1mX\u{001b}[0m]");
+
+    let encoded = encode_data(&signature, data_encoding);
+    match signature_output_location {
+        Some(location) => {
+            write_output(&location.to_string_lossy(), &encoded).expect("Failed to write signature");
+            println!("~ Writing to file [\u{001b}[32;1mX\u{001b}[0m]");
+        }
+        None => println!("\n{}", String::from_utf8_lossy(&encoded).trim_end()),
+    }
+}
+
+fn verify_signature_dialog() {
+    let (public_key_file_location, _, key) =
+        match prompt_key("the signer's public key", "./signer-public-key.pem") {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                println!("\u{001b}[31;1mFailed to load key: {}\u{001b}[0m", e);
+                return;
+            }
+        };
+    let signer = key.public_key();
+
+    let file_location = prompt_existing_file("the signed file", "./message.txt");
+    let signature_file_location = prompt_existing_file("the signature", "./signature.bin");
+    let (signature_encoding, data_encoding) = select_data_encoding("the signature");
+
+    println!("\n\n\u{001b}[1mHere are the details of the signature to verify:\n\u{001b}[0m");
+    println!("Signature scheme: \t\tRSASSA-PKCS1-V1_5 with SHA-256");
+    println!(
+        "Public key file location: \t{}",
+        public_key_file_location.to_string_lossy()
+    );
+    println!("File location: \t\t\t{}", file_location.to_string_lossy());
+    println!(
+        "Signature file location: \t{}",
+        signature_file_location.to_string_lossy()
+    );
+    println!("Signature encoding: \t\t{}", signature_encoding);
+
+    if !confirm("Proceed with verification?") {
+        println!("\u{001b}[31;1mAborting verification\u{001b}[0m");
+        return;
+    }
+
+    println!("\u{001b}[32;1mProceeding with verification\u{001b}[0m");
+
+    let message = read_input(&file_location.to_string_lossy());
+    let signature = read_input(&signature_file_location.to_string_lossy())
+        .and_then(|bytes| decode_data(&bytes, data_encoding));
+    let result = message.and_then(|message| {
+        signature.and_then(|signature| rsassa_pkcs1_v1_5_verify(&signer, &message, &signature))
+    });
+
+    match result {
+        Ok(()) => println!("\n\u{001b}[32;1mSignature is valid\u{001b}[0m"),
+        Err(e) => println!(
+            "\n\u{001b}[31;1mSignature verification failed: {}\u{001b}[0m",
+            e
+        ),
+    }
+}
+
+fn inspect_key_dialog() {
+    let (key_file_location, key_format, key) = match prompt_key("the key", "./private-key.pem") {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            println!("\u{001b}[31;1mFailed to load key: {}\u{001b}[0m", e);
+            return;
+        }
+    };
+
+    println!(
+        "\n\n\u{001b}[1mHere are the details of {}:\n\u{001b}[0m",
+        key_file_location.to_string_lossy()
+    );
+    print!("{}", KeyReport::new(&key, key_format));
+}
+
+/// Asks for a key's location, and for its passphrase if it is encrypted.
+/// The format of the key is detected.
+fn prompt_key(description: &str, default: &str) -> Result<(PathBuf, KeyFormat, Key), String> {
+    let location = prompt_existing_file(&format!("{} file", description), default);
+
+    let bytes = read_input(&location.to_string_lossy())?;
+    let passphrase = if is_encrypted(&bytes) {
+        let passphrase = rpassword::prompt_password(
+            "\n\u{001b}[33;1mThe key is encrypted, enter its passphrase> \u{001b}[0m",
+        )
+        .map_err(|e| format!("failed to read passphrase: {}", e))?;
+        Some(passphrase)
+    } else {
+        None
+    };
+
+    let (key, format) = parse_key(&bytes, None, passphrase.as_deref())?;
+    Ok((location, format, key))
+}
+
+/// Asks for one of `options` until a valid index is entered.
+fn select(title: &str, prompt: &str, options: &[&str]) -> usize {
+    loop {
+        println!("\n\u{001b}[33;1m{}\u{001b}[0m", title);
+        for (i, &my_string) in options.iter().enumerate() {
+            println!("  \u{001b}[32;1m{}\u{001b}[0m: {}", i, my_string);
+        }
+
+        print!("\n{} (0-{})> ", prompt, options.len() - 1);
+        let _unused = std::io::stdout().flush();
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap();
+
+        match input.trim().parse::<usize>() {
+            Ok(num) if num < options.len() => break num,
+            _ => println!("\u{001b}[31;1mInvalid selection\u{001b}[0m"),
+        }
+    }
+}
+
+fn select_data_encoding(description: &str) -> (&'static str, DataEncoding) {
+    let encodings = ["Binary", "Hex", "Base64"];
+    let selection = select(
+        "Available Encodings",
+        &format!("Select the encoding of {}", description),
+        &encodings,
+    );
+    let encoding = match selection {
+        0 => DataEncoding::Binary,
+        1 => DataEncoding::Hex,
+        _ => DataEncoding::Base64,
+    };
+
+    (encodings[selection], encoding)
+}
+
+/// Asks for one of the schemes that encrypt a message directly with RSA.
+fn select_scheme(prompt: &str) -> Scheme {
+    let schemes = [Scheme::Pkcs1V15, Scheme::Oaep];
+    let names: Vec<String> = schemes.iter().map(Scheme::to_string).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    schemes[select("Available Encryption Schemes", prompt, &names)]
+}
+
+fn confirm(question: &str) -> bool {
+    loop {
+        print!("\n\u{001b}[33;1m{} (y or n)> \u{001b}[0m", question);
+        let _unused = std::io::stdout().flush();
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap();
+
+        match input.trim() {
+            "y" => break true,
+            "n" => break false,
+            _ => println!("\u{001b}[31;1mInvalid selection\u{001b}[0m"),
+        }
+    }
+}
+
+fn prompt_existing_file(description: &str, default: &str) -> PathBuf {
+    loop {
+        print!(
+            "\n\u{001b}[33;1mEnter the filepath of {} ({})> \u{001b}[0m",
+            description, default
+        );
+        let _unused = std::io::stdout().flush();
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap();
+        input = input.trim().to_string();
+        if input.is_empty() {
+            input = default.to_string();
+        }
+
+        let result = PathBuf::from(input);
+
+        if !result.is_file() {
+            println!("\u{001b}[31;1mNo file exists at that location\u{001b}[0m");
+        } else {
+            break result;
+        }
+    }
+}
+
+/// Asks where to write output. Without a `default` an empty answer means
+/// printing to the terminal, which is returned as `None`.
+fn prompt_destination(description: &str, default: Option<&str>) -> Option<PathBuf> {
+    loop {
+        print!(
+            "\n\u{001b}[33;1mEnter a destination filename for {} ({})> \u{001b}[0m",
+            description,
+            default.unwrap_or("leave empty to print")
+        );
+        let _unused = std::io::stdout().flush();
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap();
+        input = input.trim().to_string();
+        if input.is_empty() {
+            match default {
+                Some(default) => input = default.to_string(),
+                None => break None,
+            }
+        }
+
+        let result = PathBuf::from(input);
+
+        if result.is_dir() {
+            println!("\u{001b}[31;1mA directory exists at that location\u{001b}[0m");
+        } else {
+            break Some(result);
+        }
+    }
+}
+
+fn print_destination(description: &str, location: &Option<PathBuf>) {
+    match location {
+        Some(location) => println!(
+            "{} output location: \t{}",
+            description,
+            location.to_string_lossy()
+        ),
+        None => println!("{} output location: \tterminal", description),
+    }
+}
+
+/// The fingerprints and randomart of a new key, so it can be recognised by
+/// eye later.
+fn describe_fingerprints(key: &RSAPublicKey) -> String {
+    format!(
+        "\n\u{001b}[1mFingerprints of your new public key:\n\u{001b}[0m\n\
+         SHA-256: \t\t\t{}\n\
+         OpenSSH SHA-256: \t\t{}\n\
+         OpenSSH MD5: \t\t\t{}\n\
+         JWK thumbprint: \t\t{}\n\n\
+         {}",
+        format_hex(&key.fingerprint_sha256()),
+        key.openssh_fingerprint_sha256(),
+        key.openssh_fingerprint_md5(),
+        key.jwk_thumbprint(),
+        key.randomart()
+    )
+}
@@ -0,0 +1,90 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+};
+
+use super::DataEncoding;
+
+/// Reads all of `path`, or of stdin for `-`.
+pub fn read_input(path: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    if path == "-" {
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("failed to read stdin: {}", e))?;
+    } else {
+        bytes = std::fs::read(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    }
+
+    Ok(bytes)
+}
+
+/// Writes `bytes` to `path`, or to stdout for `-`.
+pub fn write_output(path: &str, bytes: &[u8]) -> Result<(), String> {
+    if path == "-" {
+        let mut stdout = std::io::stdout();
+        stdout
+            .write_all(bytes)
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("failed to write stdout: {}", e))
+    } else {
+        std::fs::write(path, bytes).map_err(|e| format!("failed to write {}: {}", path, e))
+    }
+}
+
+/// Opens `path` for streaming, or stdin for `-`.
+pub fn open_input(path: &str) -> Result<Box<dyn Read>, String> {
+    if path == "-" {
+        return Ok(Box::new(std::io::stdin().lock()));
+    }
+    let file = File::open(path).map_err(|e| format!("failed to open {}: {}", path, e))?;
+    Ok(Box::new(file))
+}
+
+/// Creates `path` for streaming, or uses stdout for `-`.
+pub fn create_output(path: &str) -> Result<Box<dyn Write>, String> {
+    if path == "-" {
+        return Ok(Box::new(std::io::stdout().lock()));
+    }
+    let file = File::create(path).map_err(|e| format!("failed to create {}: {}", path, e))?;
+    Ok(Box::new(file))
+}
+
+/// Text encodings end in a newline so they print cleanly.
+pub fn encode_data(bytes: &[u8], encoding: DataEncoding) -> Vec<u8> {
+    match encoding {
+        DataEncoding::Binary => bytes.to_vec(),
+        DataEncoding::Hex => {
+            let mut hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+            hex.push('\n');
+            hex.into_bytes()
+        }
+        DataEncoding::Base64 => {
+            let mut text = base64::encode(bytes);
+            text.push('\n');
+            text.into_bytes()
+        }
+    }
+}
+
+/// Inverse of `encode_data`, ignoring whitespace in the text encodings.
+pub fn decode_data(bytes: &[u8], encoding: DataEncoding) -> Result<Vec<u8>, String> {
+    if encoding == DataEncoding::Binary {
+        return Ok(bytes.to_vec());
+    }
+
+    let text: String = String::from_utf8_lossy(bytes).split_whitespace().collect();
+    match encoding {
+        DataEncoding::Hex => {
+            if !text.len().is_multiple_of(2) || !text.is_ascii() {
+                return Err("invalid hex input".into());
+            }
+            (0..text.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&text[i..i + 2], 16))
+                .collect::<Result<_, _>>()
+                .map_err(|_| "invalid hex input".into())
+        }
+        _ => base64::decode(text).map_err(|e| format!("invalid base64 input: {}", e)),
+    }
+}
@@ -0,0 +1,343 @@
+use clap::{
+    builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+    Args, Parser, Subcommand, ValueEnum,
+};
+use naive_rsa::{container::Scheme, format::KeyFormat};
+
+pub mod commands;
+pub mod inspect;
+pub mod interactive;
+pub mod io;
+pub mod keys;
+
+/// Everything went fine.
+pub const EXIT_SUCCESS: i32 = 0;
+/// The operation failed, including a signature that does not verify.
+pub const EXIT_FAILURE: i32 = 1;
+/// The command line could not be understood. clap uses the same code.
+pub const EXIT_USAGE: i32 = 2;
+
+#[derive(Parser)]
+#[command(
+    name = "naive-rsa",
+    version,
+    about = "A naive MIT-licensed RSA implementation",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    /// Walk through an operation with prompts instead of using a subcommand
+    #[arg(long)]
+    pub interactive: bool,
+
+    /// Log debug output
+    #[arg(long, global = true)]
+    pub debug: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Generate a PKCS #1 RSAPrivateKey
+    Genkey {
+        /// Modulus size in bits
+        #[arg(long, default_value_t = 2048)]
+        bits: u32,
+        /// Number of primes, more than 2 gives a multi-prime key
+        #[arg(long, default_value_t = 2)]
+        primes: usize,
+        /// Format of the written key
+        #[arg(long, value_parser = key_format(), default_value = "pkcs1-pem")]
+        outform: KeyFormat,
+        /// Output file, or - for stdout
+        #[arg(short, long, default_value = "-")]
+        out: String,
+    },
+    /// Extract the RSAPublicKey from a private key
+    Pubkey {
+        #[command(flatten)]
+        input: KeyFile,
+        /// Format of the written key
+        #[arg(long, value_parser = key_format(), default_value = "pkcs1-pem")]
+        outform: KeyFormat,
+        /// Output file, or - for stdout
+        #[arg(short, long, default_value = "-")]
+        out: String,
+    },
+    /// Encrypt a message with RSAES-PKCS1-v1_5 or RSAES-OAEP
+    Encrypt {
+        #[command(flatten)]
+        key: KeyOption,
+        #[command(flatten)]
+        data: DataFiles,
+        /// Encryption scheme
+        #[arg(long, value_enum, default_value_t = EncryptionScheme::Pkcs1)]
+        scheme: EncryptionScheme,
+        /// Write a container recording the scheme and recipient
+        #[arg(long)]
+        container: bool,
+        /// Write an ASCII-armored container
+        #[arg(long, conflicts_with_all = ["container", "encoding"])]
+        armor: bool,
+    },
+    /// Decrypt a ciphertext, detecting containers and armor
+    Decrypt {
+        #[command(flatten)]
+        key: KeyOption,
+        #[command(flatten)]
+        data: DataFiles,
+        /// Scheme of a bare ciphertext, containers record their own
+        #[arg(long, value_enum, default_value_t = EncryptionScheme::Pkcs1)]
+        scheme: EncryptionScheme,
+    },
+    /// Encrypt a file of any size with AES-256-GCM under an RSA-OAEP wrapped key
+    Seal {
+        #[command(flatten)]
+        recipients: RecipientKeys,
+        /// Input file, or - for stdin
+        #[arg(short, long, default_value = "-")]
+        r#in: String,
+        /// Output file, or - for stdout
+        #[arg(short, long, default_value = "-")]
+        out: String,
+        /// Write an ASCII-armored container
+        #[arg(long)]
+        armor: bool,
+    },
+    /// Decrypt a file written by seal, armored or not
+    Open {
+        #[command(flatten)]
+        key: KeyOption,
+        /// Input file, or - for stdin
+        #[arg(short, long, default_value = "-")]
+        r#in: String,
+        /// Output file, or - for stdout
+        #[arg(short, long, default_value = "-")]
+        out: String,
+    },
+    /// Sign a message with RSASSA-PKCS1-v1_5 and SHA-256
+    Sign {
+        #[command(flatten)]
+        key: KeyOption,
+        #[command(flatten)]
+        data: DataFiles,
+    },
+    /// Verify an RSASSA-PKCS1-v1_5 signature made with SHA-256
+    Verify {
+        #[command(flatten)]
+        key: KeyOption,
+        /// Signed message, or - for stdin
+        #[arg(short, long, default_value = "-")]
+        r#in: String,
+        /// Signature file
+        #[arg(short, long)]
+        signature: String,
+        /// Encoding of the signature
+        #[arg(long, value_enum, default_value_t = DataEncoding::Binary)]
+        encoding: DataEncoding,
+    },
+    /// Convert a public or private key to another format
+    Convert {
+        #[command(flatten)]
+        input: KeyFile,
+        /// Format of the written key
+        #[arg(long, value_parser = key_format(), default_value = "pkcs1-pem")]
+        outform: KeyFormat,
+        /// Output file, or - for stdout
+        #[arg(short, long, default_value = "-")]
+        out: String,
+    },
+    /// Print the parameters and fingerprints of a key and check it for problems
+    Inspect {
+        #[command(flatten)]
+        input: KeyFile,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// A key read from `--in`.
+#[derive(Args)]
+pub struct KeyFile {
+    /// Key file, or - for stdin. Encrypted keys take their passphrase from
+    /// NAIVE_RSA_PASSPHRASE, or prompt for it on a terminal
+    #[arg(short, long, default_value = "-")]
+    pub r#in: String,
+    /// Format of the key, detected when omitted
+    #[arg(long, value_parser = key_format())]
+    pub inform: Option<KeyFormat>,
+}
+
+/// A key read from `--key`, leaving `--in` for the data.
+#[derive(Args)]
+pub struct KeyOption {
+    /// Key file. Encrypted keys take their passphrase from
+    /// NAIVE_RSA_PASSPHRASE, or prompt for it on a terminal
+    #[arg(short, long)]
+    pub key: String,
+    /// Format of the key, detected when omitted
+    #[arg(long, value_parser = key_format())]
+    pub inform: Option<KeyFormat>,
+}
+
+/// One or more keys to encrypt to, read from `--key`.
+#[derive(Args)]
+pub struct RecipientKeys {
+    /// Recipient's key file, repeat it to let several keys decrypt
+    #[arg(short, long, required = true)]
+    pub key: Vec<String>,
+    /// Format of the keys, detected when omitted
+    #[arg(long, value_parser = key_format())]
+    pub inform: Option<KeyFormat>,
+}
+
+#[derive(Args)]
+pub struct DataFiles {
+    /// Input file, or - for stdin
+    #[arg(short, long, default_value = "-")]
+    pub r#in: String,
+    /// Output file, or - for stdout
+    #[arg(short, long, default_value = "-")]
+    pub out: String,
+    /// Encoding of the ciphertext or signature, on whichever side it is
+    #[arg(long, value_enum, default_value_t = DataEncoding::Binary)]
+    pub encoding: DataEncoding,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DataEncoding {
+    Binary,
+    Hex,
+    Base64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EncryptionScheme {
+    /// RSAES-PKCS1-v1_5
+    Pkcs1,
+    /// RSAES-OAEP with SHA-256
+    Oaep,
+}
+
+impl From<EncryptionScheme> for Scheme {
+    fn from(scheme: EncryptionScheme) -> Self {
+        match scheme {
+            EncryptionScheme::Pkcs1 => Scheme::Pkcs1V15,
+            EncryptionScheme::Oaep => Scheme::Oaep,
+        }
+    }
+}
+
+/// How a ciphertext is written out.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// The bare ciphertext, in the chosen `DataEncoding`
+    Bare,
+    /// A container, in the chosen `DataEncoding`
+    Container,
+    /// An ASCII-armored container
+    Armored,
+}
+
+/// Parses `--inform` and `--outform`, listing the formats in `--help`. The
+/// old `pem` and `der` values still mean PKCS #1.
+fn key_format() -> impl TypedValueParser<Value = KeyFormat> {
+    let formats = KeyFormat::ALL.map(|format| match format {
+        KeyFormat::Pkcs1Pem => PossibleValue::new(format.name()).alias("pem"),
+        KeyFormat::Pkcs1Der => PossibleValue::new(format.name()).alias("der"),
+        _ => PossibleValue::new(format.name()),
+    });
+
+    PossibleValuesParser::new(formats).map(|name| {
+        name.parse::<KeyFormat>()
+            .expect("parser only accepts known formats")
+    })
+}
+
+/// Runs a subcommand and returns the process exit code.
+pub fn run(command: Command) -> i32 {
+    let result = match command {
+        Command::Genkey {
+            bits,
+            primes,
+            outform,
+            out,
+        } => commands::genkey(bits, primes, outform, &out),
+        Command::Pubkey {
+            input,
+            outform,
+            out,
+        } => commands::pubkey(&input.r#in, input.inform, outform, &out),
+        Command::Encrypt {
+            key,
+            data,
+            scheme,
+            container,
+            armor,
+        } => {
+            let framing = match (container, armor) {
+                (_, true) => Framing::Armored,
+                (true, false) => Framing::Container,
+                (false, false) => Framing::Bare,
+            };
+            commands::encrypt(
+                &key.key,
+                key.inform,
+                &data.r#in,
+                &data.out,
+                data.encoding,
+                scheme.into(),
+                framing,
+            )
+        }
+        Command::Decrypt { key, data, scheme } => commands::decrypt(
+            &key.key,
+            key.inform,
+            &data.r#in,
+            &data.out,
+            data.encoding,
+            scheme.into(),
+        ),
+        Command::Seal {
+            recipients,
+            r#in,
+            out,
+            armor,
+        } => commands::seal(&recipients.key, recipients.inform, &r#in, &out, armor),
+        Command::Open { key, r#in, out } => commands::open(&key.key, key.inform, &r#in, &out),
+        Command::Sign { key, data } => {
+            commands::sign(&key.key, key.inform, &data.r#in, &data.out, data.encoding)
+        }
+        Command::Verify {
+            key,
+            r#in,
+            signature,
+            encoding,
+        } => match commands::verify(&key.key, key.inform, &r#in, &signature, encoding) {
+            Ok(()) => {
+                println!("Verified OK");
+                Ok(())
+            }
+            Err(e) => {
+                println!("Verification failure");
+                Err(e)
+            }
+        },
+        Command::Convert {
+            input,
+            outform,
+            out,
+        } => commands::convert(&input.r#in, input.inform, outform, &out),
+        Command::Inspect { input, json } => commands::inspect(&input.r#in, input.inform, json),
+    };
+
+    match result {
+        Ok(()) => EXIT_SUCCESS,
+        Err(e) => {
+            eprintln!("naive-rsa: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
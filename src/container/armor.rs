@@ -0,0 +1,165 @@
+//! ASCII armor for containers: the container bytes in base64 between
+//! `-----BEGIN NAIVE-RSA MESSAGE-----` and `-----END NAIVE-RSA MESSAGE-----`
+//! lines, wrapped at 64 characters like PEM.
+//!
+//! `armor` and `dearmor` work on whole messages, `ArmorWriter` and
+//! `ArmorReader` stream so sealed files of any size can be armored.
+
+use std::io::{self, BufRead, ErrorKind, Read, Write};
+
+use crate::asn1::{from_pem_string, to_pem_string};
+
+use super::ContainerResult;
+
+pub const LABEL: &str = "NAIVE-RSA MESSAGE";
+
+/// Input bytes per line of 64 base64 characters.
+const LINE_BYTES: usize = 48;
+
+pub fn armor(container: &[u8]) -> String {
+    to_pem_string(LABEL, container)
+}
+
+pub fn dearmor(text: &str) -> ContainerResult<Vec<u8>> {
+    from_pem_string(LABEL, text)
+}
+
+/// Whether `bytes`, which may be just the start of the input, begin with an
+/// armor header.
+pub fn is_armored(bytes: &[u8]) -> bool {
+    bytes
+        .trim_ascii_start()
+        .starts_with(format!("-----BEGIN {}-----", LABEL).as_bytes())
+}
+
+/// Armors everything written to it. `finish` writes the last line and the
+/// footer, without it the output is incomplete.
+pub struct ArmorWriter<W: Write> {
+    inner: W,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> ArmorWriter<W> {
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        writeln!(inner, "-----BEGIN {}-----", LABEL)?;
+        Ok(Self {
+            inner,
+            pending: Vec::with_capacity(LINE_BYTES),
+        })
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.pending.is_empty() {
+            writeln!(self.inner, "{}", base64::encode(&self.pending))?;
+        }
+        writeln!(self.inner, "-----END {}-----", LABEL)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for ArmorWriter<W> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(bytes);
+
+        let mut lines = self.pending.chunks_exact(LINE_BYTES);
+        for line in &mut lines {
+            writeln!(self.inner, "{}", base64::encode(line))?;
+        }
+        self.pending = lines.remainder().to_vec();
+
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads the container out of an armored message, a line at a time.
+pub struct ArmorReader<R: BufRead> {
+    inner: R,
+    decoded: Vec<u8>,
+    position: usize,
+    /// Base64 left over from lines whose length is not a multiple of 4
+    pending: String,
+    finished: bool,
+}
+
+impl<R: BufRead> ArmorReader<R> {
+    /// Skips to the armor header, failing if there is none.
+    pub fn new(mut inner: R) -> ContainerResult<Self> {
+        let begin = format!("-----BEGIN {}-----", LABEL);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match inner.read_line(&mut line) {
+                Ok(0) | Err(_) => return Err(format!("missing \"{}\" header", begin)),
+                Ok(_) if line.trim() == begin => break,
+                Ok(_) => {}
+            }
+        }
+
+        Ok(Self {
+            inner,
+            decoded: Vec::new(),
+            position: 0,
+            pending: String::new(),
+            finished: false,
+        })
+    }
+
+    fn decode_next_line(&mut self) -> io::Result<()> {
+        let mut line = String::new();
+        if self.inner.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("missing \"-----END {}-----\" footer", LABEL),
+            ));
+        }
+
+        let line = line.trim();
+        if !line.is_ascii() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "invalid base64 in armor",
+            ));
+        }
+        self.finished = line == format!("-----END {}-----", LABEL);
+        if !self.finished {
+            self.pending.push_str(line);
+        }
+
+        let usable = if self.finished {
+            self.pending.len()
+        } else {
+            self.pending.len() / 4 * 4
+        };
+        self.decoded = base64::decode(&self.pending[..usable]).map_err(|e| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid base64 in armor: {}", e),
+            )
+        })?;
+        self.position = 0;
+        self.pending.drain(..usable);
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for ArmorReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.decoded.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.decode_next_line()?;
+        }
+
+        let length = buffer.len().min(self.decoded.len() - self.position);
+        buffer[..length].copy_from_slice(&self.decoded[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
@@ -0,0 +1,264 @@
+//! The self-describing file format for ciphertexts.
+//!
+//! A container starts with a header naming the scheme, its parameters and
+//! the keys it was encrypted for, so it can be decrypted without knowing any
+//! of that out of band, and a wrong key is reported before any RSA
+//! operation. All integers are big-endian:
+//!
+//! | Field            | Size            | Contents                                 |
+//! |------------------|-----------------|------------------------------------------|
+//! | magic            | 8               | `NAIVERSA`                               |
+//! | version          | 1               | `2`                                      |
+//! | scheme           | 1               | a `Scheme` id                            |
+//! | recipient count  | 2               | number of recipients, at least 1         |
+//! | recipients       | 32 each         | SHA-256 of each recipient's SPKI DER     |
+//! | params length    | 2               | length of the parameters                 |
+//! | parameters       | params length   | scheme specific, see `Scheme`            |
+//! | payload          | rest            | scheme specific, see `Scheme`            |
+//!
+//! Version 1 containers have no recipient count and exactly one recipient.
+//! They are still read.
+//!
+//! The ASCII-armored variant in `armor` is the same bytes in a PEM-style
+//! `NAIVE-RSA MESSAGE` block.
+
+use std::{fmt, io::Read};
+
+use crate::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    fingerprint::format_hex,
+    hybrid,
+    rsa::{
+        decrypt::{rsaes_oaep_decrypt, rsaes_pkcs1_v1_5_decrypt},
+        encrypt::{rsaes_oaep_encrypt, rsaes_pkcs1_v1_5_encrypt},
+    },
+};
+
+pub mod armor;
+
+pub const MAGIC: [u8; 8] = *b"NAIVERSA";
+/// The version written. Version 1 can still be read.
+pub const VERSION: u8 = 2;
+
+/// Parameter byte naming SHA-256 as the OAEP and MGF1 hash.
+pub(crate) const HASH_SHA256: u8 = 1;
+
+pub type ContainerResult<T> = Result<T, String>;
+
+/// SHA-256 over the SubjectPublicKeyInfo DER of a recipient's key.
+pub type Fingerprint = [u8; 32];
+
+pub fn fingerprint(key: &RSAPublicKey) -> Fingerprint {
+    key.fingerprint_sha256()
+}
+
+/// Colon-separated hex, as `inspect` prints fingerprints.
+pub fn format_fingerprint(fingerprint: &Fingerprint) -> String {
+    format_hex(fingerprint)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// RSAES-PKCS1-v1_5. No parameters, the payload is the ciphertext.
+    Pkcs1V15,
+    /// RSAES-OAEP with an empty label. The parameters are the hash id,
+    /// which is always SHA-256, and the payload is the ciphertext.
+    Oaep,
+    /// `hybrid::seal`. The parameters are the hash id of the OAEP key wrap
+    /// and the `u32` chunk size. The payload is a stanza per recipient, in
+    /// the order of the header, then the AES-256-GCM chunks. A stanza is the
+    /// `u16` length of the wrapped key and the wrapped key. Every chunk
+    /// authenticates the header and the stanzas, only the header in version 1.
+    Hybrid,
+}
+
+impl Scheme {
+    pub fn id(&self) -> u8 {
+        match self {
+            Scheme::Pkcs1V15 => 1,
+            Scheme::Oaep => 2,
+            Scheme::Hybrid => 3,
+        }
+    }
+
+    pub fn from_id(id: u8) -> ContainerResult<Self> {
+        match id {
+            1 => Ok(Scheme::Pkcs1V15),
+            2 => Ok(Scheme::Oaep),
+            3 => Ok(Scheme::Hybrid),
+            id => Err(format!("unknown container scheme {}", id)),
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Scheme::Pkcs1V15 => "RSAES-PKCS1-v1_5",
+            Scheme::Oaep => "RSAES-OAEP with SHA-256",
+            Scheme::Hybrid => "RSAES-OAEP with SHA-256 and AES-256-GCM",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub scheme: Scheme,
+    pub recipients: Vec<Fingerprint>,
+    pub parameters: Vec<u8>,
+}
+
+impl Header {
+    pub fn new(scheme: Scheme, recipients: &[RSAPublicKey], parameters: Vec<u8>) -> Self {
+        Self {
+            version: VERSION,
+            scheme,
+            recipients: recipients.iter().map(fingerprint).collect(),
+            parameters,
+        }
+    }
+
+    /// The header exactly as it was read or will be written, including its
+    /// version.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(self.version);
+        bytes.push(self.scheme.id());
+        if self.version > 1 {
+            bytes.extend_from_slice(&(self.recipients.len() as u16).to_be_bytes());
+        }
+        for recipient in &self.recipients {
+            bytes.extend_from_slice(recipient);
+        }
+        bytes.extend_from_slice(&(self.parameters.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.parameters);
+        bytes
+    }
+
+    /// Reads a header, leaving `reader` at the start of the payload.
+    pub fn read<R: Read>(reader: &mut R) -> ContainerResult<Self> {
+        let mut start = [0u8; 10];
+        reader
+            .read_exact(&mut start)
+            .map_err(|_| "input is too short to be a container")?;
+        if start[..8] != MAGIC {
+            return Err("input is not a container".into());
+        }
+
+        let version = start[8];
+        let scheme = Scheme::from_id(start[9])?;
+        let count = match version {
+            1 => 1,
+            2 => read_u16(reader)? as usize,
+            version => return Err(format!("unsupported container version {}", version)),
+        };
+        if count == 0 {
+            return Err("the container lists no recipients".into());
+        }
+
+        let mut recipients = vec![[0u8; 32]; count];
+        for recipient in recipients.iter_mut() {
+            reader
+                .read_exact(recipient)
+                .map_err(|_| "container header is truncated")?;
+        }
+        let mut parameters = vec![0u8; read_u16(reader)? as usize];
+        reader
+            .read_exact(&mut parameters)
+            .map_err(|_| "container header is truncated")?;
+
+        Ok(Self {
+            version,
+            scheme,
+            recipients,
+            parameters,
+        })
+    }
+
+    /// Where the public half of `key` is among the recipients, failing if
+    /// the container was not encrypted for it.
+    pub fn recipient_index(&self, key: &RSAPrivateKey) -> ContainerResult<usize> {
+        let expected = fingerprint(&key.into());
+        self.recipients
+            .iter()
+            .position(|recipient| recipient == &expected)
+            .ok_or_else(|| {
+                let recipients: Vec<_> = self.recipients.iter().map(format_fingerprint).collect();
+                format!(
+                    "the message is for {} {}, not {}",
+                    if recipients.len() == 1 { "key" } else { "keys" },
+                    recipients.join(", "),
+                    format_fingerprint(&expected)
+                )
+            })
+    }
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> ContainerResult<u16> {
+    let mut bytes = [0u8; 2];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|_| "container header is truncated")?;
+    Ok(u16::from_be_bytes(bytes))
+}
+
+pub fn is_container(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+}
+
+/// Encrypts `message` to `recipient` with one of the single-block schemes.
+/// Messages of any length are sealed with `hybrid::seal` instead.
+pub fn encrypt(
+    recipient: &RSAPublicKey,
+    scheme: Scheme,
+    message: &[u8],
+) -> ContainerResult<Vec<u8>> {
+    let (parameters, ciphertext) = match scheme {
+        Scheme::Pkcs1V15 => (Vec::new(), rsaes_pkcs1_v1_5_encrypt(recipient, message)?),
+        Scheme::Oaep => (
+            vec![HASH_SHA256],
+            rsaes_oaep_encrypt(recipient, message, &[])?,
+        ),
+        Scheme::Hybrid => {
+            let mut sealed = Vec::new();
+            hybrid::seal(recipient, message, &mut sealed)?;
+            return Ok(sealed);
+        }
+    };
+
+    let mut container = Header::new(scheme, std::slice::from_ref(recipient), parameters).to_bytes();
+    container.extend_from_slice(&ciphertext);
+    Ok(container)
+}
+
+/// Decrypts a container of any scheme, returning the header with the
+/// plaintext.
+pub fn decrypt(key: &RSAPrivateKey, container: &[u8]) -> ContainerResult<(Header, Vec<u8>)> {
+    let mut reader = container;
+    let header = Header::read(&mut reader)?;
+    let index = header.recipient_index(key)?;
+    let payload = reader;
+
+    if header.scheme != Scheme::Hybrid && header.recipients.len() != 1 {
+        return Err(format!(
+            "{} containers have a single recipient",
+            header.scheme
+        ));
+    }
+    let plaintext = match header.scheme {
+        Scheme::Pkcs1V15 if header.parameters.is_empty() => rsaes_pkcs1_v1_5_decrypt(key, payload)?,
+        Scheme::Oaep if header.parameters == [HASH_SHA256] => {
+            rsaes_oaep_decrypt(key, payload, &[])?
+        }
+        Scheme::Hybrid => {
+            let mut plaintext = Vec::new();
+            hybrid::open_payload(key, &header, index, payload, &mut plaintext)?;
+            plaintext
+        }
+        scheme => return Err(format!("unsupported {} parameters", scheme)),
+    };
+
+    Ok((header, plaintext))
+}
@@ -0,0 +1,119 @@
+//! Fingerprints of public keys, so keys can be compared by eye or looked up.
+//!
+//! The SHA-256 fingerprint of the SubjectPublicKeyInfo DER is the one that
+//! containers and `inspect` use. The OpenSSH fingerprints match
+//! `ssh-keygen -l`, and the randomart is the drunken bishop picture of the
+//! SHA-256 one that `ssh-keygen -lv` draws.
+
+use crate::{
+    asn1::RSAPublicKey,
+    hash::{Digest, Md5, Sha256},
+};
+
+/// Width and height of the randomart field.
+const FIELD_WIDTH: usize = 17;
+const FIELD_HEIGHT: usize = 9;
+
+/// How often the bishop visited a square, from none to 14 and more, then
+/// its start and end squares.
+const RANDOMART_SYMBOLS: &[u8; 17] = b" .o+=*BOX@%&#/^SE";
+
+/// Colon-separated lowercase hex, as fingerprints are usually written.
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+impl RSAPublicKey {
+    /// SHA-256 over the SubjectPublicKeyInfo DER, the same as
+    /// `openssl pkey -pubout -outform DER | sha256sum`.
+    pub fn fingerprint_sha256(&self) -> [u8; 32] {
+        Sha256::digest(&self.to_spki_der_vec())
+            .try_into()
+            .expect("SHA-256 hashes are 32 bytes")
+    }
+
+    /// The MD5 fingerprint of the OpenSSH public key blob as
+    /// `ssh-keygen -l -E md5` prints it, `MD5:` and colon-separated hex.
+    pub fn openssh_fingerprint_md5(&self) -> String {
+        format!("MD5:{}", format_hex(&Md5::digest(&self.to_openssh_blob())))
+    }
+
+    /// The SHA-256 fingerprint of the OpenSSH public key blob as
+    /// `ssh-keygen -l` prints it, `SHA256:` and unpadded base64.
+    pub fn openssh_fingerprint_sha256(&self) -> String {
+        format!(
+            "SHA256:{}",
+            base64::encode_config(
+                Sha256::digest(&self.to_openssh_blob()),
+                base64::STANDARD_NO_PAD
+            )
+        )
+    }
+
+    /// The RFC 7638 JWK thumbprint with SHA-256 in unpadded base64url: the
+    /// hash of the required members in lexicographic order, without
+    /// whitespace.
+    pub fn jwk_thumbprint(&self) -> String {
+        let encode = |value: &[u8]| base64::encode_config(value, base64::URL_SAFE_NO_PAD);
+        let members = format!(
+            r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+            encode(&self.public_exponent.to_bytes_be()),
+            encode(&self.modulus.to_bytes_be())
+        );
+        encode(&Sha256::digest(members.as_bytes()))
+    }
+
+    /// The randomart of the OpenSSH SHA-256 fingerprint, as `ssh-keygen -lv`
+    /// draws it. The lines are joined without a trailing newline.
+    ///
+    /// A bishop starts in the middle of the field and makes four diagonal
+    /// moves per byte of the hash, two bits each from the lowest, stopping
+    /// at the walls. Each square shows how often it was visited.
+    pub fn randomart(&self) -> String {
+        let end = RANDOMART_SYMBOLS.len() - 1;
+        let mut field = [[0usize; FIELD_WIDTH]; FIELD_HEIGHT];
+        let (mut x, mut y) = (FIELD_WIDTH / 2, FIELD_HEIGHT / 2);
+
+        for mut byte in Sha256::digest(&self.to_openssh_blob()) {
+            for _ in 0..4 {
+                x = if byte & 0x01 != 0 {
+                    (x + 1).min(FIELD_WIDTH - 1)
+                } else {
+                    x.saturating_sub(1)
+                };
+                y = if byte & 0x02 != 0 {
+                    (y + 1).min(FIELD_HEIGHT - 1)
+                } else {
+                    y.saturating_sub(1)
+                };
+                if field[y][x] < end - 2 {
+                    field[y][x] += 1;
+                }
+                byte >>= 2;
+            }
+        }
+        field[FIELD_HEIGHT / 2][FIELD_WIDTH / 2] = end - 1;
+        field[y][x] = end;
+
+        let border = |label: &str| {
+            let left = (FIELD_WIDTH - label.len()) / 2;
+            let right = FIELD_WIDTH - left - label.len();
+            format!("+{}{}{}+", "-".repeat(left), label, "-".repeat(right))
+        };
+
+        let mut lines = vec![border(&format!("[RSA {}]", self.modulus.bits()))];
+        for row in &field {
+            let squares: String = row
+                .iter()
+                .map(|&visits| RANDOMART_SYMBOLS[visits] as char)
+                .collect();
+            lines.push(format!("|{}|", squares));
+        }
+        lines.push(border("[SHA256]"));
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,178 @@
+use asn1_rs::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asn1::{OtherPrimeInfo, RSAPrivateKey, RSAPublicKey},
+    rsa::{blinding::Blinding, precompute::MontgomeryCache},
+};
+
+/// An RSA JSON Web Key from RFC 7518 section 6.3. Every integer is the
+/// unpadded base64url encoding of its big-endian bytes.
+#[derive(Serialize, Deserialize)]
+struct Jwk {
+    kty: String,
+    n: String,
+    e: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    d: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dq: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    qi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oth: Option<Vec<JwkOtherPrime>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JwkOtherPrime {
+    r: String,
+    d: String,
+    t: String,
+}
+
+fn encode(value: &BigUint) -> String {
+    base64::encode_config(value.to_bytes_be(), base64::URL_SAFE_NO_PAD)
+}
+
+fn decode(value: &str, name: &str) -> Result<BigUint, String> {
+    base64::decode_config(value, base64::URL_SAFE_NO_PAD)
+        .map(|bytes| BigUint::from_bytes_be(&bytes))
+        .map_err(|e| format!("invalid base64url in JWK \"{}\": {}", name, e))
+}
+
+fn decode_required(value: &Option<String>, name: &str) -> Result<BigUint, String> {
+    decode(
+        value
+            .as_deref()
+            .ok_or(format!("JWK is missing \"{}\"", name))?,
+        name,
+    )
+}
+
+fn parse(json: &str) -> Result<Jwk, String> {
+    let jwk: Jwk = serde_json::from_str(json).map_err(|e| format!("malformed JWK: {}", e))?;
+    if jwk.kty != "RSA" {
+        return Err(format!("JWK key type is {}, not RSA", jwk.kty));
+    }
+    Ok(jwk)
+}
+
+/// Whether `json` is an object with a `d` member, which only private JWKs
+/// have.
+pub(crate) fn is_private(json: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(json).is_ok_and(|jwk| jwk.get("d").is_some())
+}
+
+impl RSAPublicKey {
+    pub fn to_jwk_string(&self) -> String {
+        let jwk = Jwk {
+            kty: "RSA".into(),
+            n: encode(&self.modulus),
+            e: encode(&self.public_exponent),
+            d: None,
+            p: None,
+            q: None,
+            dp: None,
+            dq: None,
+            qi: None,
+            oth: None,
+        };
+        serde_json::to_string_pretty(&jwk).expect("Failed to serialize JWK") + "\n"
+    }
+
+    /// Reads the public members of a public or private JWK.
+    pub fn from_jwk_string(json: &str) -> Result<Self, String> {
+        let jwk = parse(json)?;
+        Ok(Self::new(decode(&jwk.n, "n")?, decode(&jwk.e, "e")?))
+    }
+}
+
+impl RSAPrivateKey {
+    pub fn to_jwk_string(&self) -> String {
+        let oth = self
+            .other_prime_infos
+            .iter()
+            .map(|info| JwkOtherPrime {
+                r: encode(&info.prime),
+                d: encode(&info.exponent),
+                t: encode(&info.coefficient),
+            })
+            .collect::<Vec<_>>();
+
+        let jwk = Jwk {
+            kty: "RSA".into(),
+            n: encode(&self.modulus),
+            e: encode(&self.public_exponent),
+            d: Some(encode(&self.private_exponent)),
+            p: Some(encode(&self.prime1)),
+            q: Some(encode(&self.prime2)),
+            dp: Some(encode(&self.exponent1)),
+            dq: Some(encode(&self.exponent2)),
+            qi: Some(encode(&self.coefficient)),
+            oth: (!oth.is_empty()).then_some(oth),
+        };
+        serde_json::to_string_pretty(&jwk).expect("Failed to serialize JWK") + "\n"
+    }
+
+    /// Requires every CRT member, a JWK with only `d` is rejected, and the
+    /// primes must multiply to `n`.
+    pub fn from_jwk_string(json: &str) -> Result<Self, String> {
+        let jwk = parse(json)?;
+        if jwk.d.is_none() {
+            return Err("JWK is a public key".into());
+        }
+
+        let other_prime_infos = jwk
+            .oth
+            .iter()
+            .flatten()
+            .map(|other| {
+                Ok(OtherPrimeInfo {
+                    prime: decode(&other.r, "r")?,
+                    exponent: decode(&other.d, "d")?,
+                    coefficient: decode(&other.t, "t")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let modulus = decode(&jwk.n, "n")?;
+        let prime1 = decode_required(&jwk.p, "p")?;
+        let prime2 = decode_required(&jwk.q, "q")?;
+        let one = BigUint::from(1u64);
+        let primes = || {
+            [&prime1, &prime2]
+                .into_iter()
+                .chain(other_prime_infos.iter().map(|info| &info.prime))
+        };
+        if primes().any(|prime| prime <= &one)
+            || primes().fold(one.clone(), |product, prime| product * prime) != modulus
+        {
+            return Err("JWK primes do not match the modulus".into());
+        }
+
+        Ok(Self {
+            version: BigUint::from(if other_prime_infos.is_empty() {
+                0u64
+            } else {
+                1u64
+            }),
+            modulus,
+            public_exponent: decode(&jwk.e, "e")?,
+            private_exponent: decode_required(&jwk.d, "d")?,
+            prime1,
+            prime2,
+            exponent1: decode_required(&jwk.dp, "dp")?,
+            exponent2: decode_required(&jwk.dq, "dq")?,
+            coefficient: decode_required(&jwk.qi, "qi")?,
+            other_prime_infos,
+            blinding: Blinding::default(),
+            montgomery: MontgomeryCache::default(),
+        })
+    }
+}
@@ -0,0 +1,248 @@
+//! Reading and writing keys in the file formats other tools use.
+//!
+//! PKCS #1 lives in `asn1`, the other formats are implemented on the key
+//! types in the submodules here. `load_key` works out the format of its
+//! input, `save_key` writes a chosen one.
+
+use std::{fmt, str::FromStr};
+
+use crate::asn1::{RSAPrivateKey, RSAPublicKey};
+
+pub mod jwk;
+pub mod openssh;
+mod pbes2;
+pub mod pkcs8;
+
+pub type KeyFormatResult<T> = Result<T, String>;
+
+/// A private or public key, as found in a key file.
+pub enum Key {
+    Private(Box<RSAPrivateKey>),
+    Public(RSAPublicKey),
+}
+
+impl Key {
+    /// The key itself, or the public half of a private key.
+    pub fn public_key(&self) -> RSAPublicKey {
+        match self {
+            Key::Private(key) => key.as_ref().into(),
+            Key::Public(key) => RSAPublicKey::new(key.modulus.clone(), key.public_exponent.clone()),
+        }
+    }
+
+    pub fn into_private_key(self) -> KeyFormatResult<RSAPrivateKey> {
+        match self {
+            Key::Private(key) => Ok(*key),
+            Key::Public(_) => Err("a private key is required".into()),
+        }
+    }
+}
+
+impl From<RSAPrivateKey> for Key {
+    fn from(key: RSAPrivateKey) -> Self {
+        Key::Private(Box::new(key))
+    }
+}
+
+impl From<RSAPublicKey> for Key {
+    fn from(key: RSAPublicKey) -> Self {
+        Key::Public(key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// `RSA PRIVATE KEY` or `RSA PUBLIC KEY`
+    Pkcs1Pem,
+    Pkcs1Der,
+    /// `PRIVATE KEY`, for private keys only
+    Pkcs8Pem,
+    Pkcs8Der,
+    /// `PUBLIC KEY`, for public keys only
+    SpkiPem,
+    SpkiDer,
+    /// `OPENSSH PRIVATE KEY` or an `ssh-rsa` line
+    OpenSsh,
+    Jwk,
+}
+
+impl KeyFormat {
+    pub const ALL: [KeyFormat; 8] = [
+        KeyFormat::Pkcs1Pem,
+        KeyFormat::Pkcs1Der,
+        KeyFormat::Pkcs8Pem,
+        KeyFormat::Pkcs8Der,
+        KeyFormat::SpkiPem,
+        KeyFormat::SpkiDer,
+        KeyFormat::OpenSsh,
+        KeyFormat::Jwk,
+    ];
+
+    /// The name accepted by `from_str`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyFormat::Pkcs1Pem => "pkcs1-pem",
+            KeyFormat::Pkcs1Der => "pkcs1-der",
+            KeyFormat::Pkcs8Pem => "pkcs8-pem",
+            KeyFormat::Pkcs8Der => "pkcs8-der",
+            KeyFormat::SpkiPem => "spki-pem",
+            KeyFormat::SpkiDer => "spki-der",
+            KeyFormat::OpenSsh => "openssh",
+            KeyFormat::Jwk => "jwk",
+        }
+    }
+
+    pub fn is_binary(&self) -> bool {
+        matches!(
+            self,
+            KeyFormat::Pkcs1Der | KeyFormat::Pkcs8Der | KeyFormat::SpkiDer
+        )
+    }
+}
+
+impl fmt::Display for KeyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            KeyFormat::Pkcs1Pem => "PKCS #1 PEM",
+            KeyFormat::Pkcs1Der => "PKCS #1 DER",
+            KeyFormat::Pkcs8Pem => "PKCS #8 PEM",
+            KeyFormat::Pkcs8Der => "PKCS #8 DER",
+            KeyFormat::SpkiPem => "SPKI PEM",
+            KeyFormat::SpkiDer => "SPKI DER",
+            KeyFormat::OpenSsh => "OpenSSH",
+            KeyFormat::Jwk => "JWK",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+impl FromStr for KeyFormat {
+    type Err = String;
+
+    /// Takes the names from `name`, plus `pem` and `der` for PKCS #1.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "pem" => Ok(KeyFormat::Pkcs1Pem),
+            "der" => Ok(KeyFormat::Pkcs1Der),
+            name => KeyFormat::ALL
+                .into_iter()
+                .find(|format| format.name() == name)
+                .ok_or_else(|| {
+                    let names: Vec<_> = KeyFormat::ALL.iter().map(|f| f.name()).collect();
+                    format!("unknown key format, expected one of {}", names.join(", "))
+                }),
+        }
+    }
+}
+
+/// The label of the first PEM block in `text`, if any.
+fn pem_label(text: &str) -> Option<&str> {
+    let start = text.find("-----BEGIN ")? + "-----BEGIN ".len();
+    let length = text[start..].find("-----")?;
+    Some(&text[start..start + length])
+}
+
+/// Parses a key in any supported format, returning the format it was in.
+///
+/// Text formats are told apart by their PEM label or leading characters.
+/// DER input is tried as each structure in turn.
+pub fn load_key(bytes: &[u8]) -> KeyFormatResult<(Key, KeyFormat)> {
+    let text = std::str::from_utf8(bytes).map(str::trim_start);
+
+    let format = match text {
+        Ok(text) if text.starts_with('{') => KeyFormat::Jwk,
+        Ok(text) if text.starts_with("ssh-rsa ") => KeyFormat::OpenSsh,
+        Ok(text) => match pem_label(text) {
+            Some("RSA PRIVATE KEY") | Some("RSA PUBLIC KEY") => KeyFormat::Pkcs1Pem,
+            Some("PRIVATE KEY") => KeyFormat::Pkcs8Pem,
+            Some("PUBLIC KEY") => KeyFormat::SpkiPem,
+            Some("OPENSSH PRIVATE KEY") => KeyFormat::OpenSsh,
+            Some("ENCRYPTED PRIVATE KEY") => {
+                return Err("encrypted PKCS #8 keys need a passphrase, use \
+                            RSAPrivateKey::from_encrypted_pkcs8_pem_string"
+                    .into())
+            }
+            Some(label) => return Err(format!("unsupported PEM block \"{}\"", label)),
+            None => return Err("no key found in input".into()),
+        },
+        Err(_) => {
+            // A DER structure of one kind rarely parses as another, so the
+            // first success is taken
+            for format in [KeyFormat::Pkcs1Der, KeyFormat::Pkcs8Der, KeyFormat::SpkiDer] {
+                if let Ok(key) = load_key_as(bytes, format) {
+                    return Ok((key, format));
+                }
+            }
+            return Err("no PKCS #1, PKCS #8 or SPKI key found in DER input".into());
+        }
+    };
+
+    Ok((load_key_as(bytes, format)?, format))
+}
+
+/// Parses a key that is known to be in `format`.
+pub fn load_key_as(bytes: &[u8], format: KeyFormat) -> KeyFormatResult<Key> {
+    if format.is_binary() {
+        return match format {
+            KeyFormat::Pkcs1Der => RSAPrivateKey::from_pkcs1_der(bytes)
+                .map(Key::from)
+                .or_else(|_| RSAPublicKey::from_pkcs1_der(bytes).map(Key::from))
+                .map_err(|_| "no PKCS #1 RSA key found in DER input".into()),
+            KeyFormat::Pkcs8Der => RSAPrivateKey::from_pkcs8_der(bytes).map(Key::from),
+            _ => RSAPublicKey::from_spki_der(bytes).map(Key::from),
+        };
+    }
+
+    let text =
+        std::str::from_utf8(bytes).map_err(|_| format!("{} key is not valid UTF-8", format))?;
+    let label = pem_label(text);
+    match format {
+        KeyFormat::Pkcs1Pem if label == Some("RSA PRIVATE KEY") => {
+            RSAPrivateKey::from_pkcs1_pem_string(text).map(Key::from)
+        }
+        KeyFormat::Pkcs1Pem if label == Some("RSA PUBLIC KEY") => {
+            RSAPublicKey::from_pkcs1_pem_string(text).map(Key::from)
+        }
+        KeyFormat::Pkcs1Pem => Err("no PKCS #1 RSA key found in PEM input".into()),
+        KeyFormat::Pkcs8Pem => RSAPrivateKey::from_pkcs8_pem_string(text).map(Key::from),
+        KeyFormat::SpkiPem => RSAPublicKey::from_spki_pem_string(text).map(Key::from),
+        KeyFormat::OpenSsh if label.is_some() => {
+            RSAPrivateKey::from_openssh_string(text).map(Key::from)
+        }
+        KeyFormat::OpenSsh => RSAPublicKey::from_openssh_string(text).map(Key::from),
+        _ => {
+            if jwk::is_private(text) {
+                RSAPrivateKey::from_jwk_string(text).map(Key::from)
+            } else {
+                RSAPublicKey::from_jwk_string(text).map(Key::from)
+            }
+        }
+    }
+}
+
+/// Encodes `key` in `format`. Public keys cannot be written as PKCS #8 and
+/// private keys cannot be written as SPKI, use `Key::public_key` first.
+pub fn save_key(key: &Key, format: KeyFormat) -> KeyFormatResult<Vec<u8>> {
+    let encoded = match (key, format) {
+        (Key::Private(key), KeyFormat::Pkcs1Pem) => key.to_pkcs1_pem_string().into_bytes(),
+        (Key::Private(key), KeyFormat::Pkcs1Der) => key.to_pkcs1_der_vec(),
+        (Key::Private(key), KeyFormat::Pkcs8Pem) => key.to_pkcs8_pem_string().into_bytes(),
+        (Key::Private(key), KeyFormat::Pkcs8Der) => key.to_pkcs8_der_vec(),
+        (Key::Private(key), KeyFormat::OpenSsh) => key.to_openssh_string()?.into_bytes(),
+        (Key::Private(key), KeyFormat::Jwk) => key.to_jwk_string().into_bytes(),
+        (Key::Private(_), _) => {
+            return Err(format!("{} holds public keys only", format));
+        }
+        (Key::Public(key), KeyFormat::Pkcs1Pem) => key.to_pkcs1_pem_string().into_bytes(),
+        (Key::Public(key), KeyFormat::Pkcs1Der) => key.to_pkcs1_der_vec(),
+        (Key::Public(key), KeyFormat::SpkiPem) => key.to_spki_pem_string().into_bytes(),
+        (Key::Public(key), KeyFormat::SpkiDer) => key.to_spki_der_vec(),
+        (Key::Public(key), KeyFormat::OpenSsh) => key.to_openssh_string().into_bytes(),
+        (Key::Public(key), KeyFormat::Jwk) => key.to_jwk_string().into_bytes(),
+        (Key::Public(_), _) => {
+            return Err(format!("{} holds private keys only", format));
+        }
+    };
+
+    Ok(encoded)
+}
@@ -0,0 +1,216 @@
+use asn1_rs::BigUint;
+use rand::{thread_rng, RngCore};
+
+use crate::{
+    asn1::{from_pem_string, to_pem_string, RSAPrivateKey, RSAPublicKey},
+    rsa::{blinding::Blinding, precompute::MontgomeryCache},
+};
+
+const KEY_TYPE: &str = "ssh-rsa";
+const PRIVATE_KEY_MAGIC: &[u8] = b"openssh-key-v1\0";
+const PRIVATE_KEY_LABEL: &str = "OPENSSH PRIVATE KEY";
+
+fn write_string(blob: &mut Vec<u8>, bytes: &[u8]) {
+    blob.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    blob.extend_from_slice(bytes);
+}
+
+/// An mpint from RFC 4251 section 5, which is two's complement and so needs
+/// a zero byte in front of a set top bit.
+fn write_mpint(blob: &mut Vec<u8>, value: &BigUint) {
+    if value == &BigUint::from(0u64) {
+        write_string(blob, &[]);
+        return;
+    }
+
+    let magnitude = value.to_bytes_be();
+    if magnitude[0] & 0x80 != 0 {
+        write_string(blob, &[&[0u8][..], &magnitude].concat());
+    } else {
+        write_string(blob, &magnitude);
+    }
+}
+
+/// Reads the fields of an SSH wire-format blob in order.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_u32(&mut self) -> Result<u32, String> {
+        if self.bytes.len() < 4 {
+            return Err("truncated OpenSSH key".into());
+        }
+        let (value, rest) = self.bytes.split_at(4);
+        self.bytes = rest;
+        Ok(u32::from_be_bytes(value.try_into().expect("4 bytes")))
+    }
+
+    fn read_string(&mut self) -> Result<&'a [u8], String> {
+        let length = self.read_u32()? as usize;
+        if self.bytes.len() < length {
+            return Err("truncated OpenSSH key".into());
+        }
+        let (value, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(value)
+    }
+
+    fn read_mpint(&mut self) -> Result<BigUint, String> {
+        let bytes = self.read_string()?;
+        if bytes.first().is_some_and(|byte| byte & 0x80 != 0) {
+            return Err("negative integer in OpenSSH key".into());
+        }
+        Ok(BigUint::from_bytes_be(bytes))
+    }
+
+    fn expect_key_type(&mut self) -> Result<(), String> {
+        let key_type = self.read_string()?;
+        if key_type != KEY_TYPE.as_bytes() {
+            return Err(format!(
+                "OpenSSH key is {}, not {}",
+                String::from_utf8_lossy(key_type),
+                KEY_TYPE
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl RSAPublicKey {
+    /// The RFC 4253 section 6.6 `ssh-rsa` public key blob.
+    pub fn to_openssh_blob(&self) -> Vec<u8> {
+        let mut blob = Vec::new();
+        write_string(&mut blob, KEY_TYPE.as_bytes());
+        write_mpint(&mut blob, &self.public_exponent);
+        write_mpint(&mut blob, &self.modulus);
+        blob
+    }
+
+    pub fn from_openssh_blob(blob: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes: blob };
+        reader.expect_key_type()?;
+        let public_exponent = reader.read_mpint()?;
+        let modulus = reader.read_mpint()?;
+        if !reader.bytes.is_empty() {
+            return Err("trailing data after OpenSSH public key".into());
+        }
+
+        Ok(Self::new(modulus, public_exponent))
+    }
+
+    /// A single `authorized_keys` style line without a comment.
+    pub fn to_openssh_string(&self) -> String {
+        format!("{} {}\n", KEY_TYPE, base64::encode(self.to_openssh_blob()))
+    }
+
+    /// Parses an `authorized_keys` style line, ignoring any comment.
+    pub fn from_openssh_string(line: &str) -> Result<Self, String> {
+        let mut fields = line.split_whitespace();
+        if fields.next() != Some(KEY_TYPE) {
+            return Err(format!(
+                "OpenSSH public key does not start with {}",
+                KEY_TYPE
+            ));
+        }
+        let blob = fields
+            .next()
+            .ok_or("OpenSSH public key is missing its key data")?;
+        let blob = base64::decode(blob)
+            .map_err(|e| format!("invalid base64 in OpenSSH public key: {}", e))?;
+
+        Self::from_openssh_blob(&blob)
+    }
+}
+
+impl RSAPrivateKey {
+    /// An unencrypted `openssh-key-v1` private key as written by
+    /// `ssh-keygen`, which only supports two-prime keys.
+    pub fn to_openssh_string(&self) -> Result<String, String> {
+        if !self.other_prime_infos.is_empty() {
+            return Err("OpenSSH keys must have exactly two primes".into());
+        }
+
+        let public_key = RSAPublicKey::from(self);
+
+        // The repeated check integer lets a reader detect a wrong passphrase
+        let check = thread_rng().next_u32().to_be_bytes();
+        let mut private_section = [check, check].concat();
+        write_string(&mut private_section, KEY_TYPE.as_bytes());
+        write_mpint(&mut private_section, &self.modulus);
+        write_mpint(&mut private_section, &self.public_exponent);
+        write_mpint(&mut private_section, &self.private_exponent);
+        write_mpint(&mut private_section, &self.coefficient);
+        write_mpint(&mut private_section, &self.prime1);
+        write_mpint(&mut private_section, &self.prime2);
+        write_string(&mut private_section, b"");
+        let mut padding = 1u8;
+        while private_section.len() % 8 != 0 {
+            private_section.push(padding);
+            padding += 1;
+        }
+
+        let mut blob = PRIVATE_KEY_MAGIC.to_vec();
+        write_string(&mut blob, b"none");
+        write_string(&mut blob, b"none");
+        write_string(&mut blob, b"");
+        blob.extend_from_slice(&1u32.to_be_bytes());
+        write_string(&mut blob, &public_key.to_openssh_blob());
+        write_string(&mut blob, &private_section);
+
+        Ok(to_pem_string(PRIVATE_KEY_LABEL, &blob))
+    }
+
+    pub fn from_openssh_string(pem: &str) -> Result<Self, String> {
+        let blob = from_pem_string(PRIVATE_KEY_LABEL, pem)?;
+        let body = blob
+            .strip_prefix(PRIVATE_KEY_MAGIC)
+            .ok_or("not an openssh-key-v1 private key")?;
+
+        let mut reader = Reader { bytes: body };
+        let cipher = reader.read_string()?;
+        let _kdf = reader.read_string()?;
+        let _kdf_options = reader.read_string()?;
+        if cipher != b"none" {
+            return Err("encrypted OpenSSH keys are not supported".into());
+        }
+        if reader.read_u32()? != 1 {
+            return Err("OpenSSH files with several keys are not supported".into());
+        }
+        let _public_key = reader.read_string()?;
+
+        let mut private_section = Reader {
+            bytes: reader.read_string()?,
+        };
+        if private_section.read_u32()? != private_section.read_u32()? {
+            return Err("OpenSSH key check integers differ".into());
+        }
+        private_section.expect_key_type()?;
+        let modulus = private_section.read_mpint()?;
+        let public_exponent = private_section.read_mpint()?;
+        let private_exponent = private_section.read_mpint()?;
+        let coefficient = private_section.read_mpint()?;
+        let prime1 = private_section.read_mpint()?;
+        let prime2 = private_section.read_mpint()?;
+
+        let one = BigUint::from(1u64);
+        if prime1 <= one || prime2 <= one || &prime1 * &prime2 != modulus {
+            return Err("OpenSSH key primes do not match the modulus".into());
+        }
+
+        Ok(Self {
+            version: BigUint::from(0u64),
+            exponent1: &private_exponent % (&prime1 - 1u64),
+            exponent2: &private_exponent % (&prime2 - 1u64),
+            modulus,
+            public_exponent,
+            private_exponent,
+            prime1,
+            prime2,
+            coefficient,
+            other_prime_infos: Vec::new(),
+            blinding: Blinding::default(),
+            montgomery: MontgomeryCache::default(),
+        })
+    }
+}
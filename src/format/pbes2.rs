@@ -0,0 +1,240 @@
+//! PBES2 decryption of `ENCRYPTED PRIVATE KEY` files (RFC 8018 section 6.2),
+//! limited to what OpenSSL and other tools write: PBKDF2 with an HMAC-SHA
+//! PRF, and AES in CBC mode.
+
+use aes_gcm::aes::{
+    cipher::{Block, BlockDecrypt, KeyInit},
+    Aes128, Aes192, Aes256,
+};
+use asn1_rs::{FromDer, Null, OctetString, Oid, ParseResult, Sequence};
+
+use crate::{
+    asn1::parse_integer,
+    hash::{HashAlgorithm, Sha1, Sha224, Sha256, Sha384, Sha512},
+    kdf::pbkdf2,
+};
+
+const PBES2_OID: [u64; 7] = [1, 2, 840, 113549, 1, 5, 13];
+const PBKDF2_OID: [u64; 7] = [1, 2, 840, 113549, 1, 5, 12];
+
+/// The hmacWithSHA OIDs end in the number given here under this arc.
+const HMAC_OID_ARC: [u64; 6] = [1, 2, 840, 113549, 2, 0];
+const HMAC_ALGORITHMS: [(u64, HashAlgorithm); 5] = [
+    (7, HashAlgorithm::Sha1),
+    (8, HashAlgorithm::Sha224),
+    (9, HashAlgorithm::Sha256),
+    (10, HashAlgorithm::Sha384),
+    (11, HashAlgorithm::Sha512),
+];
+
+/// aes128-CBC, aes192-CBC and aes256-CBC end in 2, 22 and 42 under this arc.
+const AES_OID_ARC: [u64; 9] = [2, 16, 840, 1, 101, 3, 4, 1, 0];
+
+const AES_BLOCK_SIZE: usize = 16;
+
+fn oid(arc: &[u64]) -> Oid<'static> {
+    Oid::from(arc).expect("valid OID")
+}
+
+/// `arc` with its last component replaced by `last`.
+fn oid_in(arc: &[u64], last: u64) -> Oid<'static> {
+    let mut components = arc.to_vec();
+    *components.last_mut().expect("arcs are not empty") = last;
+    oid(&components)
+}
+
+/// Parses an AlgorithmIdentifier, leaving its parameters to `parameters`.
+fn parse_algorithm<'a, T>(
+    i: &'a [u8],
+    parameters: impl FnOnce(&Oid<'a>, &'a [u8]) -> ParseResult<'a, T>,
+) -> ParseResult<'a, T> {
+    Sequence::from_der_and_then(i, |i| {
+        let (i, oid) = Oid::from_der(i)?;
+        parameters(&oid, i)
+    })
+}
+
+struct Pbkdf2Parameters {
+    salt: Vec<u8>,
+    iterations: u32,
+    key_length: Option<usize>,
+    prf: Option<HashAlgorithm>,
+}
+
+struct Pbes2Parameters {
+    kdf: Pbkdf2Parameters,
+    cipher: Option<usize>, // AES key length in bytes
+    iv: Vec<u8>,
+}
+
+fn parse_pbkdf2_parameters(i: &[u8]) -> ParseResult<'_, Pbkdf2Parameters> {
+    Sequence::from_der_and_then(i, |i| {
+        let (i, salt) = OctetString::from_der(i)?;
+        let (i, iterations) = parse_integer(i)?;
+        let iterations = u32::try_from(iterations)
+            .map_err(|_| asn1_rs::Err::Error(asn1_rs::Error::IntegerTooLarge))?;
+
+        // keyLength and prf are optional, prf defaulting to hmacWithSHA1
+        let (i, key_length) = match i.first() {
+            Some(0x02) => {
+                let (i, key_length) = parse_integer(i)?;
+                let key_length = usize::try_from(key_length)
+                    .map_err(|_| asn1_rs::Err::Error(asn1_rs::Error::IntegerTooLarge))?;
+                (i, Some(key_length))
+            }
+            _ => (i, None),
+        };
+        let (i, prf) = if i.is_empty() {
+            (i, Some(HashAlgorithm::Sha1))
+        } else {
+            parse_algorithm(i, |oid, i| {
+                let prf = HMAC_ALGORITHMS
+                    .into_iter()
+                    .find(|&(last, _)| oid == &oid_in(&HMAC_OID_ARC, last))
+                    .map(|(_, prf)| prf);
+                let i = if i.is_empty() {
+                    i
+                } else {
+                    Null::from_der(i)?.0
+                };
+                Ok((i, prf))
+            })?
+        };
+
+        Ok((
+            i,
+            Pbkdf2Parameters {
+                salt: salt.as_ref().to_vec(),
+                iterations,
+                key_length,
+                prf,
+            },
+        ))
+    })
+}
+
+fn parse_encrypted_private_key_info(
+    i: &[u8],
+) -> ParseResult<'_, (Option<Pbes2Parameters>, Vec<u8>)> {
+    Sequence::from_der_and_then(i, |i| {
+        let (i, parameters) = parse_algorithm(i, |algorithm, i| {
+            if algorithm != &oid(&PBES2_OID) {
+                return Ok((&[][..], None));
+            }
+
+            Sequence::from_der_and_then(i, |i| {
+                let (i, kdf) = parse_algorithm(i, |kdf, i| {
+                    if kdf != &oid(&PBKDF2_OID) {
+                        return Ok((&[][..], None));
+                    }
+                    parse_pbkdf2_parameters(i).map(|(i, kdf)| (i, Some(kdf)))
+                })?;
+                let (i, (cipher, iv)) = parse_algorithm(i, |cipher, i| {
+                    let key_length = [(2, 16), (22, 24), (42, 32)]
+                        .into_iter()
+                        .find(|&(last, _)| cipher == &oid_in(&AES_OID_ARC, last))
+                        .map(|(_, key_length)| key_length);
+                    if key_length.is_none() {
+                        return Ok((&[][..], (None, Vec::new())));
+                    }
+                    let (i, iv) = OctetString::from_der(i)?;
+                    Ok((i, (key_length, iv.as_ref().to_vec())))
+                })?;
+
+                Ok((i, kdf.map(|kdf| Pbes2Parameters { kdf, cipher, iv })))
+            })
+        })?;
+        let (i, encrypted_data) = OctetString::from_der(i)?;
+
+        Ok((i, (parameters, encrypted_data.as_ref().to_vec())))
+    })
+}
+
+fn derive_key(
+    prf: HashAlgorithm,
+    passphrase: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    match prf {
+        HashAlgorithm::Sha1 => pbkdf2::<Sha1>(passphrase, salt, iterations, length),
+        HashAlgorithm::Sha224 => pbkdf2::<Sha224>(passphrase, salt, iterations, length),
+        HashAlgorithm::Sha256 => pbkdf2::<Sha256>(passphrase, salt, iterations, length),
+        HashAlgorithm::Sha384 => pbkdf2::<Sha384>(passphrase, salt, iterations, length),
+        HashAlgorithm::Sha512 => pbkdf2::<Sha512>(passphrase, salt, iterations, length),
+    }
+}
+
+/// AES-CBC decryption without removing the padding.
+fn cbc_decrypt<C: BlockDecrypt + KeyInit>(key: &[u8], iv: &[u8], data: &[u8]) -> Vec<u8> {
+    let cipher = C::new_from_slice(key).expect("key length matches the cipher");
+
+    let mut previous = iv.to_vec();
+    let mut plaintext = Vec::with_capacity(data.len());
+    for chunk in data.chunks_exact(AES_BLOCK_SIZE) {
+        let mut block = Block::<C>::clone_from_slice(chunk);
+        cipher.decrypt_block(&mut block);
+        plaintext.extend(block.iter().zip(&previous).map(|(a, b)| a ^ b));
+        previous = chunk.to_vec();
+    }
+
+    plaintext
+}
+
+/// Decrypts an EncryptedPrivateKeyInfo, returning the PrivateKeyInfo DER
+/// inside it.
+///
+/// A wrong passphrase is only noticed through the padding, so it is
+/// reported together with corrupted data.
+pub(crate) fn decrypt(der: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let (parameters, encrypted_data) = match parse_encrypted_private_key_info(der) {
+        Ok((&[], parsed)) => parsed,
+        Ok(_) => return Err("trailing data after EncryptedPrivateKeyInfo".into()),
+        Err(e) => return Err(format!("malformed EncryptedPrivateKeyInfo: {}", e)),
+    };
+
+    let parameters = parameters.ok_or("only PBES2 with PBKDF2 encrypted keys are supported")?;
+    let kdf = &parameters.kdf;
+    let prf = kdf
+        .prf
+        .ok_or("unsupported PBKDF2 PRF, only HMAC with SHA-1 or SHA-2 is supported")?;
+    let key_length = parameters
+        .cipher
+        .ok_or("unsupported PBES2 cipher, only AES-CBC is supported")?;
+    if kdf.key_length.is_some_and(|length| length != key_length) {
+        return Err("PBKDF2 key length does not match the cipher".into());
+    }
+    if parameters.iv.len() != AES_BLOCK_SIZE {
+        return Err("AES-CBC IV must be 16 bytes".into());
+    }
+    if encrypted_data.is_empty() || encrypted_data.len() % AES_BLOCK_SIZE != 0 {
+        return Err("encrypted key is not a whole number of AES blocks".into());
+    }
+
+    let key = derive_key(
+        prf,
+        passphrase.as_bytes(),
+        &kdf.salt,
+        kdf.iterations,
+        key_length,
+    )?;
+    let mut plaintext = match key_length {
+        16 => cbc_decrypt::<Aes128>(&key, &parameters.iv, &encrypted_data),
+        24 => cbc_decrypt::<Aes192>(&key, &parameters.iv, &encrypted_data),
+        _ => cbc_decrypt::<Aes256>(&key, &parameters.iv, &encrypted_data),
+    };
+
+    // PKCS #7 padding of 1 to 16 bytes, each holding the padding length
+    let padding = *plaintext.last().expect("at least one block") as usize;
+    let valid = (1..=AES_BLOCK_SIZE).contains(&padding)
+        && plaintext[plaintext.len() - padding..]
+            .iter()
+            .all(|&byte| byte as usize == padding);
+    if !valid {
+        return Err("wrong passphrase or corrupted key".into());
+    }
+    plaintext.truncate(plaintext.len() - padding);
+
+    Ok(plaintext)
+}
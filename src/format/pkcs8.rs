@@ -0,0 +1,144 @@
+use asn1_rs::{BitString, FromDer, Null, OctetString, Oid, ParseResult, Sequence, ToDer};
+
+use crate::{
+    asn1::{
+        from_pem_string, parse_integer, to_pem_string, write_integer, RSAPrivateKey, RSAPublicKey,
+    },
+    format::pbes2,
+};
+
+/// DER of the AlgorithmIdentifier for rsaEncryption (1.2.840.113549.1.1.1)
+/// with the NULL parameters required by RFC 8017 Appendix C.
+const RSA_ENCRYPTION_ALGORITHM: [u8; 15] = [
+    0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01, 0x05, 0x00,
+];
+
+const RSA_ENCRYPTION_OID: [u64; 7] = [1, 2, 840, 113549, 1, 1, 1];
+
+/// Parses an AlgorithmIdentifier, telling whether it is rsaEncryption.
+/// Absent parameters are tolerated since some encoders leave out the NULL.
+fn parse_algorithm_identifier(i: &[u8]) -> ParseResult<'_, bool> {
+    Sequence::from_der_and_then(i, |i| {
+        let (i, oid) = Oid::from_der(i)?;
+        let i = if i.is_empty() {
+            i
+        } else {
+            Null::from_der(i)?.0
+        };
+        let rsa_encryption = Oid::from(&RSA_ENCRYPTION_OID).expect("valid OID");
+        Ok((i, oid == rsa_encryption))
+    })
+}
+
+impl RSAPrivateKey {
+    pub fn to_pkcs8_pem_string(&self) -> String {
+        to_pem_string("PRIVATE KEY", &self.to_pkcs8_der_vec())
+    }
+
+    /// PKCS #8 PrivateKeyInfo (RFC 5208) wrapping the PKCS #1 RSAPrivateKey.
+    pub fn to_pkcs8_der_vec(&self) -> Vec<u8> {
+        let mut content = Vec::new();
+        write_integer(&0u64.into(), &mut content);
+        content.extend_from_slice(&RSA_ENCRYPTION_ALGORITHM);
+        OctetString::new(&self.to_pkcs1_der_vec())
+            .write_der(&mut content)
+            .expect("Oh noez");
+
+        Sequence::new(content.into()).to_der_vec().expect("Oh noez")
+    }
+
+    pub fn from_pkcs8_pem_string(pem: &str) -> Result<Self, String> {
+        Self::from_pkcs8_der(&from_pem_string("PRIVATE KEY", pem)?)
+    }
+
+    /// Also accepts the version 1 OneAsymmetricKey of RFC 5958, whose
+    /// attributes and public key are ignored.
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, String> {
+        let parsed = Sequence::from_der_and_then(der, |i| {
+            let (i, version) = parse_integer(i)?;
+            let (i, rsa_encryption) = parse_algorithm_identifier(i)?;
+            let (_, private_key) = OctetString::from_der(i)?;
+            Ok((
+                &[][..],
+                (version, rsa_encryption, private_key.as_ref().to_vec()),
+            ))
+        });
+
+        let (version, rsa_encryption, private_key) = match parsed {
+            Ok((&[], parsed)) => parsed,
+            Ok(_) => return Err("trailing data after PrivateKeyInfo".into()),
+            Err(e) => return Err(format!("malformed PrivateKeyInfo: {}", e)),
+        };
+        if version > 1u64.into() {
+            return Err(format!("unsupported PrivateKeyInfo version {}", version));
+        }
+        if !rsa_encryption {
+            return Err("PrivateKeyInfo does not hold an RSA key".into());
+        }
+
+        Self::from_pkcs1_der(&private_key)
+    }
+
+    pub fn from_encrypted_pkcs8_pem_string(pem: &str, passphrase: &str) -> Result<Self, String> {
+        Self::from_encrypted_pkcs8_der(&from_pem_string("ENCRYPTED PRIVATE KEY", pem)?, passphrase)
+    }
+
+    /// PKCS #8 EncryptedPrivateKeyInfo (RFC 5208) as written by
+    /// `openssl pkcs8 -topk8`, encrypted with PBES2 using PBKDF2 and
+    /// AES-CBC.
+    pub fn from_encrypted_pkcs8_der(der: &[u8], passphrase: &str) -> Result<Self, String> {
+        // A wrong passphrase passes the padding check one time in 256
+        Self::from_pkcs8_der(&pbes2::decrypt(der, passphrase)?)
+            .map_err(|_| "wrong passphrase or corrupted key".into())
+    }
+}
+
+impl RSAPublicKey {
+    pub fn to_spki_pem_string(&self) -> String {
+        to_pem_string("PUBLIC KEY", &self.to_spki_der_vec())
+    }
+
+    /// X.509 SubjectPublicKeyInfo (RFC 5280) wrapping the PKCS #1
+    /// RSAPublicKey, as used by OpenSSL's `PUBLIC KEY` files.
+    pub fn to_spki_der_vec(&self) -> Vec<u8> {
+        let mut content = RSA_ENCRYPTION_ALGORITHM.to_vec();
+        BitString::new(0, &self.to_pkcs1_der_vec())
+            .write_der(&mut content)
+            .expect("Oh noez");
+
+        Sequence::new(content.into()).to_der_vec().expect("Oh noez")
+    }
+
+    pub fn from_spki_pem_string(pem: &str) -> Result<Self, String> {
+        Self::from_spki_der(&from_pem_string("PUBLIC KEY", pem)?)
+    }
+
+    pub fn from_spki_der(der: &[u8]) -> Result<Self, String> {
+        let parsed = Sequence::from_der_and_then(der, |i| {
+            let (i, rsa_encryption) = parse_algorithm_identifier(i)?;
+            let (i, public_key) = BitString::from_der(i)?;
+            Ok((
+                i,
+                (
+                    rsa_encryption,
+                    public_key.unused_bits,
+                    public_key.data.to_vec(),
+                ),
+            ))
+        });
+
+        let (rsa_encryption, unused_bits, public_key) = match parsed {
+            Ok((&[], parsed)) => parsed,
+            Ok(_) => return Err("trailing data after SubjectPublicKeyInfo".into()),
+            Err(e) => return Err(format!("malformed SubjectPublicKeyInfo: {}", e)),
+        };
+        if !rsa_encryption {
+            return Err("SubjectPublicKeyInfo does not hold an RSA key".into());
+        }
+        if unused_bits != 0 {
+            return Err("malformed SubjectPublicKeyInfo: partial byte in key".into());
+        }
+
+        Self::from_pkcs1_der(&public_key)
+    }
+}
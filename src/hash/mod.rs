@@ -0,0 +1,181 @@
+//! Hash functions from FIPS 180-4 and the MGF1 mask generation function
+//! from RFC 8017.
+//!
+//! The hashes implement `Digest`, which the RSA schemes are generic over.
+//! `HashAlgorithm` picks one at runtime, for parameters read from files or
+//! given on the command line.
+//!
+//! SHA-1 is only here for interoperability, such as OAEP with the default
+//! RFC 8017 parameters. Its collisions are practical, so it must not be used
+//! for new signatures. MD5 is only for legacy OpenSSH fingerprints and is
+//! left out of `HashAlgorithm`.
+
+mod md5;
+mod sha1;
+mod sha256;
+mod sha512;
+
+pub use md5::Md5;
+pub use sha1::Sha1;
+pub use sha256::{Sha224, Sha256};
+pub use sha512::{Sha384, Sha512};
+
+pub trait Digest: Clone {
+    /// Length of the hash in bytes
+    const OUTPUT_SIZE: usize;
+    /// Length of the blocks the compression function takes in bytes
+    const BLOCK_SIZE: usize;
+
+    fn new() -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self) -> Vec<u8>;
+
+    /// The hash of `data` in one call.
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+/// MGF1 from RFC 8017 appendix B.2.1, stretching `seed` into a mask of
+/// `length` bytes.
+pub fn mgf1<D: Digest>(seed: &[u8], length: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(length + D::OUTPUT_SIZE);
+    let mut counter = 0u32;
+    while mask.len() < length {
+        let mut hasher = D::new();
+        hasher.update(seed);
+        hasher.update(&counter.to_be_bytes());
+        mask.extend_from_slice(&hasher.finalize());
+        counter = counter.checked_add(1).expect("MGF1 mask too long");
+    }
+
+    mask.truncate(length);
+    mask
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub fn output_size(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => Sha1::OUTPUT_SIZE,
+            HashAlgorithm::Sha224 => Sha224::OUTPUT_SIZE,
+            HashAlgorithm::Sha256 => Sha256::OUTPUT_SIZE,
+            HashAlgorithm::Sha384 => Sha384::OUTPUT_SIZE,
+            HashAlgorithm::Sha512 => Sha512::OUTPUT_SIZE,
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => Sha1::BLOCK_SIZE,
+            HashAlgorithm::Sha224 => Sha224::BLOCK_SIZE,
+            HashAlgorithm::Sha256 => Sha256::BLOCK_SIZE,
+            HashAlgorithm::Sha384 => Sha384::BLOCK_SIZE,
+            HashAlgorithm::Sha512 => Sha512::BLOCK_SIZE,
+        }
+    }
+
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        self.digest_parts(&[data])
+    }
+
+    /// The hash of `parts` concatenated.
+    pub fn digest_parts(&self, parts: &[&[u8]]) -> Vec<u8> {
+        fn digest<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize()
+        }
+
+        match self {
+            HashAlgorithm::Sha1 => digest::<Sha1>(parts),
+            HashAlgorithm::Sha224 => digest::<Sha224>(parts),
+            HashAlgorithm::Sha256 => digest::<Sha256>(parts),
+            HashAlgorithm::Sha384 => digest::<Sha384>(parts),
+            HashAlgorithm::Sha512 => digest::<Sha512>(parts),
+        }
+    }
+}
+
+/// The message schedule shared by all the hashes: input is gathered into
+/// blocks of `N` bytes for the compression function, and the last block is
+/// padded with a 1 bit, zeros and the message length in bits, encoded as
+/// each hash specifies.
+#[derive(Clone)]
+struct BlockBuffer<const N: usize> {
+    block: [u8; N],
+    filled: usize,
+    length: u128,
+}
+
+impl<const N: usize> BlockBuffer<N> {
+    fn new() -> Self {
+        Self {
+            block: [0u8; N],
+            filled: 0,
+            length: 0,
+        }
+    }
+
+    /// Buffers `data`, passing each block as it fills up to `compress`.
+    fn update(&mut self, mut data: &[u8], mut compress: impl FnMut(&[u8; N])) {
+        self.length += data.len() as u128;
+
+        if self.filled > 0 {
+            let taken = data.len().min(N - self.filled);
+            self.block[self.filled..self.filled + taken].copy_from_slice(&data[..taken]);
+            self.filled += taken;
+            data = &data[taken..];
+            if self.filled < N {
+                return;
+            }
+            compress(&self.block);
+            self.filled = 0;
+        }
+
+        let mut blocks = data.chunks_exact(N);
+        for block in &mut blocks {
+            compress(block.try_into().expect("chunks are a block long"));
+        }
+        let rest = blocks.remainder();
+        self.block[..rest.len()].copy_from_slice(rest);
+        self.filled = rest.len();
+    }
+
+    /// The length of the message so far in bits.
+    fn bit_length(&self) -> u128 {
+        self.length.wrapping_mul(8)
+    }
+
+    /// Pads the message, ending it with `encoded_length`, and passes the last
+    /// one or two blocks to `compress`.
+    fn finish(mut self, encoded_length: &[u8], mut compress: impl FnMut(&[u8; N])) {
+        let length_size = encoded_length.len();
+
+        self.block[self.filled] = 0x80;
+        self.filled += 1;
+        if self.filled > N - length_size {
+            self.block[self.filled..].fill(0x00);
+            compress(&self.block);
+            self.filled = 0;
+        }
+
+        self.block[self.filled..N - length_size].fill(0x00);
+        self.block[N - length_size..].copy_from_slice(encoded_length);
+        compress(&self.block);
+    }
+}
@@ -0,0 +1,298 @@
+//! Hybrid encryption of messages of any size.
+//!
+//! `seal` wraps a random AES-256 content key with RSAES-OAEP for the
+//! recipient, or for each of several with `seal_to_recipients`. It then
+//! encrypts the message with AES-256-GCM in chunks of `CHUNK_SIZE`, so
+//! neither side holds more than one chunk in memory. The chunks follow the
+//! STREAM construction of Hoang, Reyhanitabar, Rogaway and Vizár: each nonce
+//! is the chunk counter plus a flag marking the last chunk, so dropping,
+//! reordering or truncating chunks fails authentication.
+//!
+//! The output is a `Scheme::Hybrid` container. Every chunk authenticates the
+//! container header and the recipient stanzas after it as associated data,
+//! so neither can be altered either. Version 1 containers only authenticate
+//! the header.
+
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, KeyInit, Nonce,
+};
+use rand::{thread_rng, RngCore};
+
+use crate::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    container::{
+        armor::{is_armored, ArmorReader},
+        Header, Scheme, HASH_SHA256,
+    },
+    rsa::{decrypt::rsaes_oaep_decrypt, encrypt::rsaes_oaep_encrypt},
+};
+
+/// Plaintext bytes per chunk. Every chunk but the last is exactly this long.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Largest chunk size `open` accepts from a header, bounding its memory use.
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+const TAG_SIZE: usize = 16;
+const TOO_MANY_CHUNKS: &str = "the stream has more chunks than the 64-bit counter can number";
+const CONTENT_KEY_SIZE: usize = 32;
+
+pub type HybridResult<T> = Result<T, String>;
+
+/// Encrypts everything `plaintext` yields to `recipient`, writing the
+/// container to `sealed`. Wrap `sealed` in an `ArmorWriter` for text output.
+pub fn seal<R: Read, W: Write>(
+    recipient: &RSAPublicKey,
+    plaintext: R,
+    sealed: W,
+) -> HybridResult<()> {
+    seal_to_recipients(std::slice::from_ref(recipient), plaintext, sealed)
+}
+
+/// Same as `seal`, but any one of `recipients` can open the result.
+pub fn seal_to_recipients<R: Read, W: Write>(
+    recipients: &[RSAPublicKey],
+    plaintext: R,
+    mut sealed: W,
+) -> HybridResult<()> {
+    if recipients.is_empty() {
+        return Err("at least one recipient is required".into());
+    }
+    if recipients.len() > u16::MAX as usize {
+        return Err("too many recipients".into());
+    }
+
+    let mut parameters = vec![HASH_SHA256];
+    parameters.extend_from_slice(&(CHUNK_SIZE as u32).to_be_bytes());
+    let header = Header::new(Scheme::Hybrid, recipients, parameters);
+    for (i, recipient) in header.recipients.iter().enumerate() {
+        if header.recipients[..i].contains(recipient) {
+            return Err("the same recipient is listed twice".into());
+        }
+    }
+
+    let mut content_key = [0u8; CONTENT_KEY_SIZE];
+    thread_rng().fill_bytes(&mut content_key);
+    let mut stanzas = Vec::new();
+    for recipient in recipients {
+        let wrapped_key = rsaes_oaep_encrypt(recipient, &content_key, &[])
+            .map_err(|_| "a recipient's key is too small to wrap a content key")?;
+        stanzas.extend_from_slice(&(wrapped_key.len() as u16).to_be_bytes());
+        stanzas.extend_from_slice(&wrapped_key);
+    }
+
+    write_all(&mut sealed, &header.to_bytes())?;
+    write_all(&mut sealed, &stanzas)?;
+    let associated_data = associated_data(&header, &stanzas);
+    encrypt_chunks(
+        &content_key,
+        &associated_data,
+        CHUNK_SIZE,
+        plaintext,
+        sealed,
+    )
+}
+
+/// Decrypts a container written by `seal` or `seal_to_recipients`, armored
+/// or not, using the stanza for `key`.
+///
+/// Chunks are written out as soon as they are authenticated, so on error
+/// whatever reached `plaintext` must be discarded: a truncated stream is
+/// only detected at its end.
+pub fn open<R: Read, W: Write>(key: &RSAPrivateKey, sealed: R, plaintext: W) -> HybridResult<()> {
+    let mut sealed = BufReader::new(sealed);
+    let armored = is_armored(
+        sealed
+            .fill_buf()
+            .map_err(|e| format!("failed to read input: {}", e))?,
+    );
+    let mut sealed: Box<dyn Read> = if armored {
+        Box::new(ArmorReader::new(sealed)?)
+    } else {
+        Box::new(sealed)
+    };
+
+    let header = Header::read(&mut sealed)?;
+    if header.scheme != Scheme::Hybrid {
+        return Err(format!(
+            "the container holds {}, not a sealed file",
+            header.scheme
+        ));
+    }
+    let index = header.recipient_index(key)?;
+
+    open_payload(key, &header, index, sealed, plaintext)
+}
+
+/// Decrypts the payload of a `Scheme::Hybrid` container after its header,
+/// with `key` being recipient `index`.
+pub(crate) fn open_payload<R: Read, W: Write>(
+    key: &RSAPrivateKey,
+    header: &Header,
+    index: usize,
+    mut payload: R,
+    plaintext: W,
+) -> HybridResult<()> {
+    let chunk_size = match header.parameters[..] {
+        [HASH_SHA256, a, b, c, d] => u32::from_be_bytes([a, b, c, d]) as usize,
+        _ => return Err(format!("unsupported {} parameters", header.scheme)),
+    };
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(format!("unsupported chunk size {}", chunk_size));
+    }
+
+    let mut stanzas = Vec::new();
+    let mut wrapped_key = Vec::new();
+    for i in 0..header.recipients.len() {
+        let mut length = [0u8; 2];
+        read_exact(&mut payload, &mut length)?;
+        let mut stanza = vec![0u8; u16::from_be_bytes(length) as usize];
+        read_exact(&mut payload, &mut stanza)?;
+        stanzas.extend_from_slice(&length);
+        stanzas.extend_from_slice(&stanza);
+        if i == index {
+            wrapped_key = stanza;
+        }
+    }
+
+    let content_key = rsaes_oaep_decrypt(key, &wrapped_key, &[])
+        .map_err(|_| "the content key could not be unwrapped")?;
+    if content_key.len() != CONTENT_KEY_SIZE {
+        return Err("the content key has the wrong length".into());
+    }
+
+    decrypt_chunks(
+        &content_key,
+        &associated_data(header, &stanzas),
+        chunk_size,
+        payload,
+        plaintext,
+    )
+}
+
+/// What every chunk authenticates: the header followed by the stanzas, or
+/// only the header in version 1.
+fn associated_data(header: &Header, stanzas: &[u8]) -> Vec<u8> {
+    let mut associated_data = header.to_bytes();
+    if header.version > 1 {
+        associated_data.extend_from_slice(stanzas);
+    }
+    associated_data
+}
+
+/// The 96-bit GCM nonce: three zero bytes, the 64-bit big-endian chunk
+/// counter and the last flag. A stream long enough to overflow the counter
+/// is refused rather than reusing a nonce.
+fn chunk_nonce(counter: u64, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+fn encrypt_chunks<R: Read, W: Write>(
+    content_key: &[u8],
+    associated_data: &[u8],
+    chunk_size: usize,
+    plaintext: R,
+    mut sealed: W,
+) -> HybridResult<()> {
+    let cipher = Aes256Gcm::new_from_slice(content_key).expect("32-byte key");
+    let mut counter = 0u64;
+
+    for_each_chunk(plaintext, chunk_size, |chunk, last| {
+        let payload = Payload {
+            msg: chunk,
+            aad: associated_data,
+        };
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&chunk_nonce(counter, last)), payload)
+            .map_err(|_| "chunk encryption failed")?;
+        counter = counter.checked_add(1).ok_or(TOO_MANY_CHUNKS)?;
+        write_all(&mut sealed, &ciphertext)
+    })?;
+
+    sealed
+        .flush()
+        .map_err(|e| format!("failed to write output: {}", e))
+}
+
+fn decrypt_chunks<R: Read, W: Write>(
+    content_key: &[u8],
+    associated_data: &[u8],
+    chunk_size: usize,
+    sealed: R,
+    mut plaintext: W,
+) -> HybridResult<()> {
+    let cipher = Aes256Gcm::new_from_slice(content_key).expect("32-byte key");
+    let mut counter = 0u64;
+
+    for_each_chunk(sealed, chunk_size + TAG_SIZE, |chunk, last| {
+        let payload = Payload {
+            msg: chunk,
+            aad: associated_data,
+        };
+        let decrypted = cipher
+            .decrypt(Nonce::from_slice(&chunk_nonce(counter, last)), payload)
+            .map_err(|_| "payload authentication failed, the data is corrupt or truncated")?;
+        counter = counter.checked_add(1).ok_or(TOO_MANY_CHUNKS)?;
+        write_all(&mut plaintext, &decrypted)
+    })?;
+
+    plaintext
+        .flush()
+        .map_err(|e| format!("failed to write output: {}", e))
+}
+
+/// Calls `f` with consecutive chunks of `size` bytes from `reader` and
+/// whether each is the last one. The last chunk may be shorter, or empty
+/// when the input is.
+fn for_each_chunk<R: Read>(
+    mut reader: R,
+    size: usize,
+    mut f: impl FnMut(&[u8], bool) -> HybridResult<()>,
+) -> HybridResult<()> {
+    // Reading one byte past the chunk tells whether another one follows
+    let mut buffer = vec![0u8; size + 1];
+    let mut carried = 0;
+    loop {
+        let length = carried + read_full(&mut reader, &mut buffer[carried..])?;
+        if length <= size {
+            return f(&buffer[..length], true);
+        }
+
+        f(&buffer[..size], false)?;
+        buffer[0] = buffer[size];
+        carried = 1;
+    }
+}
+
+/// Fills as much of `buffer` as the input allows.
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> HybridResult<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(format!("failed to read input: {}", e)),
+        }
+    }
+    Ok(filled)
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> HybridResult<()> {
+    if read_full(reader, buffer)? != buffer.len() {
+        return Err("sealed input is truncated".into());
+    }
+    Ok(())
+}
+
+fn write_all<W: Write>(writer: &mut W, bytes: &[u8]) -> HybridResult<()> {
+    writer
+        .write_all(bytes)
+        .map_err(|e| format!("failed to write output: {}", e))
+}
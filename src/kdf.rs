@@ -0,0 +1,141 @@
+//! HMAC from RFC 2104 and the key derivation functions built on it, HKDF
+//! from RFC 5869 and PBKDF2 from RFC 8018.
+//!
+//! All of them are generic over `hash::Digest`. HKDF turns a secret that is
+//! already high in entropy, such as a key agreement or KEM output, into
+//! keys. PBKDF2 is for passwords, where the iteration count is what slows
+//! down guessing.
+
+use crate::hash::Digest;
+
+pub type KdfResult<T> = Result<T, String>;
+
+/// HMAC with `D`, fed incrementally like the hash itself. A keyed `Hmac` can
+/// be cloned to MAC several messages without hashing the key again.
+#[derive(Clone)]
+pub struct Hmac<D: Digest> {
+    inner: D,
+    outer: D,
+}
+
+impl<D: Digest> Hmac<D> {
+    pub fn new(key: &[u8]) -> Self {
+        let mut padded_key = if key.len() > D::BLOCK_SIZE {
+            D::digest(key)
+        } else {
+            key.to_vec()
+        };
+        padded_key.resize(D::BLOCK_SIZE, 0x00);
+
+        let inner_key: Vec<u8> = padded_key.iter().map(|byte| byte ^ 0x36).collect();
+        let outer_key: Vec<u8> = padded_key.iter().map(|byte| byte ^ 0x5c).collect();
+
+        let mut inner = D::new();
+        inner.update(&inner_key);
+        let mut outer = D::new();
+        outer.update(&outer_key);
+
+        Self { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+}
+
+/// The HMAC of `message` under `key` in one call.
+pub fn hmac<D: Digest>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<D>::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+/// HKDF-Extract, concentrating the entropy of `secret` into a pseudorandom
+/// key. An empty `salt` is the same as the hash length of zeros.
+pub fn hkdf_extract<D: Digest>(salt: &[u8], secret: &[u8]) -> Vec<u8> {
+    hmac::<D>(salt, secret)
+}
+
+/// HKDF-Expand, deriving `length` bytes bound to `info` from a pseudorandom
+/// key. At most 255 hashes can be derived.
+pub fn hkdf_expand<D: Digest>(
+    pseudorandom_key: &[u8],
+    info: &[u8],
+    length: usize,
+) -> KdfResult<Vec<u8>> {
+    if length > 255 * D::OUTPUT_SIZE {
+        return Err("HKDF output too long".into());
+    }
+
+    let keyed = Hmac::<D>::new(pseudorandom_key);
+    let mut output = Vec::with_capacity(length + D::OUTPUT_SIZE);
+    let mut block = Vec::new();
+    for counter in 1..=length.div_ceil(D::OUTPUT_SIZE) as u8 {
+        let mut mac = keyed.clone();
+        mac.update(&block);
+        mac.update(info);
+        mac.update(&[counter]);
+        block = mac.finalize();
+        output.extend_from_slice(&block);
+    }
+
+    output.truncate(length);
+    Ok(output)
+}
+
+/// HKDF-Extract followed by HKDF-Expand.
+pub fn hkdf<D: Digest>(
+    salt: &[u8],
+    secret: &[u8],
+    info: &[u8],
+    length: usize,
+) -> KdfResult<Vec<u8>> {
+    hkdf_expand::<D>(&hkdf_extract::<D>(salt, secret), info, length)
+}
+
+/// PBKDF2 with HMAC-`D` as the PRF, deriving `length` bytes from `password`.
+pub fn pbkdf2<D: Digest>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    length: usize,
+) -> KdfResult<Vec<u8>> {
+    if iterations == 0 {
+        return Err("PBKDF2 needs at least one iteration".into());
+    }
+    let blocks = length.div_ceil(D::OUTPUT_SIZE);
+    if blocks > u32::MAX as usize {
+        return Err("PBKDF2 output too long".into());
+    }
+
+    let keyed = Hmac::<D>::new(password);
+    let mut output = Vec::with_capacity(blocks * D::OUTPUT_SIZE);
+    for index in 1..=blocks as u32 {
+        // T_i = U_1 ^ U_2 ^ ... ^ U_c, with U_1 = PRF(P, S || INT(i)) and
+        // U_j = PRF(P, U_{j-1})
+        let mut mac = keyed.clone();
+        mac.update(salt);
+        mac.update(&index.to_be_bytes());
+        let mut u = mac.finalize();
+
+        let mut block = u.clone();
+        for _ in 1..iterations {
+            let mut mac = keyed.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            for (byte, mask) in block.iter_mut().zip(&u) {
+                *byte ^= mask;
+            }
+        }
+        output.extend_from_slice(&block);
+    }
+
+    output.truncate(length);
+    Ok(output)
+}
@@ -1,4 +1,12 @@
+pub mod arith;
 pub mod asn1;
+pub mod bignum;
+pub mod container;
+pub mod fingerprint;
+pub mod format;
+pub mod hash;
+pub mod hybrid;
+pub mod kdf;
 pub mod math;
 pub mod prime;
 pub mod rsa;
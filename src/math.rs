@@ -1,80 +1,367 @@
-use num_bigint::{BigInt, BigUint};
+use std::cmp::Ordering;
 
-pub fn mod_inverse(a: &BigInt, m: &BigInt) -> BigInt {
-    let mut a = a.clone();
-    let mut m = m.clone();
-    let m0 = m.clone();
-    let mut y = BigInt::from(0u64);
-    let mut x = BigInt::from(1u64);
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use rand::thread_rng;
 
-    if m == BigInt::from(1u64) {
-        return BigInt::from(0u64);
-    }
+use crate::{
+    arith::{limbs_for_bits, MontgomeryContext},
+    bignum::{Backend, BigNum},
+    prime::small_primes,
+};
+
+/// Extended Euclidean algorithm, returning `(g, x, y)` such that
+/// `a * x + b * y = g` where `g` is the non-negative gcd of `a` and `b`.
+pub fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_x, mut x) = (BigInt::from(1u64), BigInt::from(0u64));
+    let (mut old_y, mut y) = (BigInt::from(0u64), BigInt::from(1u64));
 
-    while a > BigInt::from(1u64) {
-        // q is quotient
-        let q = &a / &m;
-        let mut t = m.clone();
+    while r != BigInt::from(0u64) {
+        let q = &old_r / &r;
 
-        // m is remainder now, process same as
-        // Euclid's algo
-        m = a % &m;
-        a = t;
-        t = y.clone();
+        let next_r = &old_r - &q * &r;
+        old_r = std::mem::replace(&mut r, next_r);
+        let next_x = &old_x - &q * &x;
+        old_x = std::mem::replace(&mut x, next_x);
+        let next_y = &old_y - &q * &y;
+        old_y = std::mem::replace(&mut y, next_y);
+    }
+
+    if old_r < BigInt::from(0u64) {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
 
-        // Update y and x
-        y = x - q * &y;
-        x = t;
+/// The inverse of `a` modulo `m` in `[0, m)`, or `None` if `a` and `m` are
+/// not coprime or `m` is not positive.
+pub fn mod_inverse(a: &BigInt, m: &BigInt) -> Option<BigInt> {
+    if m <= &BigInt::from(0u64) {
+        return None;
     }
 
-    // Make x positive
-    if x < BigInt::from(0u64) {
-        x += m0;
+    let (g, x, _) = extended_gcd(a, m);
+    if g != BigInt::from(1u64) {
+        return None;
     }
 
-    return x;
+    Some(modulo(&x, m))
+}
+
+/// `a mod m` in `[0, m)` for positive `m`, unlike `%` which keeps the sign
+/// of `a`.
+pub fn modulo(a: &BigInt, m: &BigInt) -> BigInt {
+    ((a % m) + m) % m
 }
 
+/// The least common multiple, 0 if either input is 0.
 pub fn lcm(a: &BigUint, b: &BigUint) -> BigUint {
-    a * (b / gcd(a, b))
+    let (a, b) = (Backend::from_biguint(a), Backend::from_biguint(b));
+    if a.is_zero() || b.is_zero() {
+        return BigUint::from(0u64);
+    }
+    a.mul(&b.div_rem(&binary_gcd(&a, &b)).0).to_biguint()
 }
 
 pub fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
-    let mut a: BigUint = a.clone();
-    let mut b: BigUint = b.clone();
+    binary_gcd(&Backend::from_biguint(a), &Backend::from_biguint(b)).to_biguint()
+}
+
+fn binary_gcd<N: BigNum>(a: &N, b: &N) -> N {
+    let mut a = a.clone();
+    let mut b = b.clone();
+
+    // gcd(a, 0) = a, and the loop below would never terminate on a zero
+    if a.is_zero() {
+        return b;
+    }
+    if b.is_zero() {
+        return a;
+    }
 
     let mut d = 0;
 
     loop {
         //a and b eqal
-        if &a == &b {
-            //debug!("equal");
-            return &a * BigUint::from(2u64).pow(d);
+        if a.equals(&b) {
+            return a.shl(d);
         //a is even and b is even
-        } else if &a % 2u64 == BigUint::from(0u64) && &b % 2u64 == BigUint::from(0u64) {
-            //debug!("a and b are even, d = {}", d);
-            a = &a / 2u64;
-            b = &b / 2u64;
+        } else if !a.is_odd() && !b.is_odd() {
+            a = a.shr(1);
+            b = b.shr(1);
             d += 1;
         //a is even and b is odd
-        } else if &a % 2u64 == BigUint::from(0u64) && &b % 2u64 != BigUint::from(0u64) {
-            //debug!("a is even b is odd");
-            a = &a / 2u64;
+        } else if !a.is_odd() {
+            a = a.shr(1);
         //a is odd and b is even
-        } else if &a % 2u64 != BigUint::from(0u64) && &b % 2u64 == BigUint::from(0u64) {
-            //debug!("a is odd b is even");
-            b = &b / 2u64;
+        } else if !b.is_odd() {
+            b = b.shr(1);
         //a is odd and b is odd
-        } else if &a % 2u64 != BigUint::from(0u64) && &b % 2u64 != BigUint::from(0u64) {
-            //debug!("a and b are odd");
-            if &a < &b {
-                let temp = a.clone();
-                a = b;
-                b = temp;
+        } else {
+            if a.compare(&b) == Ordering::Less {
+                std::mem::swap(&mut a, &mut b);
+            }
+
+            a = a.sub(&b).shr(1);
+        }
+    }
+}
+
+/// Factors `n = p * q` given a matching public and private exponent, using
+/// the probabilistic method of NIST SP 800-56B Appendix C.2.
+///
+/// Works for any `d` with `d * e = 1 mod lambda(n)`, including exponents
+/// computed modulo phi(n). Returns `None` if no factor was found after 100
+/// attempts, which for a consistent key happens with probability 2^-100.
+pub fn recover_primes(n: &BigUint, e: &BigUint, d: &BigUint) -> Option<(BigUint, BigUint)> {
+    let (n, e, d) = (
+        Backend::from_biguint(n),
+        Backend::from_biguint(e),
+        Backend::from_biguint(d),
+    );
+
+    factor_with_exponents(&n, &e, &d).map(|(p, q)| (p.to_biguint(), q.to_biguint()))
+}
+
+fn factor_with_exponents<N: BigNum>(n: &N, e: &N, d: &N) -> Option<(N, N)> {
+    let one = N::from_u64(1);
+    let two = N::from_u64(2);
+    if n.compare(&N::from_u64(15)) == Ordering::Less
+        || !n.is_odd()
+        || d.mul(e).compare(&one) != Ordering::Greater
+    {
+        return None;
+    }
+
+    // k = d * e - 1 = 2^t * r with r odd
+    let k = d.mul(e).sub(&one);
+    if k.is_odd() {
+        return None;
+    }
+    let t = k.trailing_zeros().unwrap();
+    let r = k.shr(t as usize);
+
+    // r comes from d, so g^r is computed in constant time, padding r to the
+    // sizes of its operands rather than its own length
+    let context = MontgomeryContext::new(&n.to_biguint())?;
+    let exponent_limbs = limbs_for_bits(n.bits().max(d.bits()) + e.bits());
+    let r = r.to_biguint();
+
+    let n_minus_one = n.sub(&one);
+    let mut rng = thread_rng();
+    for _ in 0..100 {
+        let g = rng.gen_biguint_range(&BigUint::from(2u64), &n_minus_one.to_biguint());
+        let mut y = N::from_biguint(&context.pow_with_width(&g, &r, exponent_limbs));
+        if y.equals(&one) || y.equals(&n_minus_one) {
+            continue;
+        }
+
+        for _ in 1..=t {
+            let x = y.mod_pow(&two, n);
+            if x.equals(&one) {
+                // y is a nontrivial square root of 1 mod n
+                let p = binary_gcd(&y.sub(&one), n);
+                let q = n.div_rem(&p).0;
+                return Some((p, q));
             }
+            if x.equals(&n_minus_one) {
+                break;
+            }
+            y = x;
+        }
+    }
 
-            let c = &a - &b;
-            a = c / 2u64;
+    None
+}
+
+/// Solves the system `x = a_i mod m_i` for the given `(a_i, m_i)` pairs,
+/// returning `(x, m)` with `x` in `[0, m)` and `m` the lcm of the moduli.
+///
+/// The moduli need not be coprime, in which case `None` is returned if the
+/// congruences contradict each other. Every modulus must be positive.
+pub fn crt(congruences: &[(BigInt, BigInt)]) -> Option<(BigInt, BigInt)> {
+    let mut x = BigInt::from(0u64);
+    let mut m = BigInt::from(1u64);
+
+    for (a_i, m_i) in congruences {
+        if m_i <= &BigInt::from(0u64) {
+            return None;
         }
+
+        // x + m * t = a_i mod m_i  =>  m * t = a_i - x mod m_i
+        let (g, inverse, _) = extended_gcd(&m, m_i);
+        let difference = a_i - &x;
+        if &difference % &g != BigInt::from(0u64) {
+            return None;
+        }
+
+        let m_i_reduced = m_i / &g;
+        let t = modulo(&((difference / &g) * inverse), &m_i_reduced);
+
+        x += &m * t;
+        m *= m_i_reduced;
+        x = modulo(&x, &m);
+    }
+
+    Some((x, m))
+}
+
+/// The Jacobi symbol `(a / n)` for odd positive `n`, or `None` otherwise.
+pub fn jacobi(a: &BigInt, n: &BigInt) -> Option<i8> {
+    if n <= &BigInt::from(0u64) || n % 2u64 == BigInt::from(0u64) {
+        return None;
     }
+
+    let mut a = modulo(a, n);
+    let mut n = n.clone();
+    let mut result = 1i8;
+
+    while a != BigInt::from(0u64) {
+        // (2 / n) = -1 exactly when n = 3 or 5 mod 8
+        while &a % 2u64 == BigInt::from(0u64) {
+            a /= 2u64;
+            let n_mod_8 = &n % 8u64;
+            if n_mod_8 == BigInt::from(3u64) || n_mod_8 == BigInt::from(5u64) {
+                result = -result;
+            }
+        }
+
+        // Quadratic reciprocity flips the sign when both are 3 mod 4
+        std::mem::swap(&mut a, &mut n);
+        if &a % 4u64 == BigInt::from(3u64) && &n % 4u64 == BigInt::from(3u64) {
+            result = -result;
+        }
+        a %= &n;
+    }
+
+    if n == BigInt::from(1u64) {
+        Some(result)
+    } else {
+        Some(0)
+    }
+}
+
+/// The Legendre symbol `(a / p)` for an odd prime `p`.
+///
+/// Primality of `p` is not checked; for composite `p` this is the Jacobi
+/// symbol, which no longer tells whether `a` is a square.
+pub fn legendre(a: &BigInt, p: &BigInt) -> Option<i8> {
+    jacobi(a, p)
+}
+
+/// The largest integer `r` with `r^k <= n`.
+pub fn nth_root(n: &BigUint, k: u32) -> BigUint {
+    n.nth_root(k)
+}
+
+/// If `n = b^k` for some integers `b` and `k >= 2`, returns the pair with the
+/// smallest base.
+pub fn is_perfect_power(n: &BigUint) -> Option<(BigUint, u32)> {
+    if n <= &BigUint::from(1u64) {
+        return None;
+    }
+
+    // Checking prime exponents from the largest down finds the smallest base,
+    // composite exponents are covered since b^(jk) = (b^j)^k
+    let mut best: Option<(BigUint, u32)> = None;
+    for k in small_primes(n.bits() + 1).into_iter().rev() {
+        let k = k as u32;
+        let root = nth_root(n, k);
+        if root.pow(k) == *n {
+            best = match is_perfect_power(&root) {
+                Some((base, j)) => Some((base, j * k)),
+                None => Some((root, k)),
+            };
+            break;
+        }
+    }
+
+    best
+}
+
+/// How far `tonelli_shanks` searches for a quadratic non-residue. The least
+/// non-residue of a prime is tiny in practice, so running out of candidates
+/// means `p` is composite.
+const MAX_NON_RESIDUE_CANDIDATES: u64 = 1000;
+
+/// A square root of `a` modulo an odd prime `p` using the Tonelli-Shanks
+/// algorithm, or `None` if `a` is a quadratic non-residue.
+///
+/// The other root is `p - r`. Primality of `p` is not checked, and for a
+/// composite `p` the result is `None` whenever the algorithm breaks down.
+pub fn sqrt_mod(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    if p <= &BigUint::from(2u64) || !p.bit(0) {
+        return None;
+    }
+
+    tonelli_shanks(&Backend::from_biguint(a), &Backend::from_biguint(p)).map(|r| r.to_biguint())
+}
+
+fn tonelli_shanks<N: BigNum>(a: &N, p: &N) -> Option<N> {
+    let one = N::from_u64(1);
+    let is_residue = |x: &N| legendre(&x.to_biguint().into(), &p.to_biguint().into());
+
+    let a = a.rem(p);
+    if a.is_zero() {
+        return Some(a);
+    }
+    if is_residue(&a) != Some(1) {
+        return None;
+    }
+
+    // p - 1 = q * 2^s with q odd
+    let p_minus_one = p.sub(&one);
+    let s = p_minus_one.trailing_zeros().unwrap();
+    let q = p_minus_one.shr(s as usize);
+
+    // A composite p can run to completion with a wrong answer, so every
+    // result is squared back before it is returned
+    let checked = |r: N| r.mod_mul(&r, p).equals(&a).then_some(r);
+
+    if s == 1 {
+        return checked(a.mod_pow(&p.add(&one).shr(2), p));
+    }
+
+    // Any quadratic non-residue works as the generator of the 2-Sylow subgroup.
+    // A composite p that is a perfect square has none, every Jacobi symbol
+    // being a square itself
+    let root = nth_root(&p.to_biguint(), 2);
+    if &root * &root == p.to_biguint() {
+        return None;
+    }
+    let mut z = N::from_u64(2);
+    while is_residue(&z) != Some(-1) {
+        z = z.add(&one);
+        if z.compare(&N::from_u64(MAX_NON_RESIDUE_CANDIDATES)) == Ordering::Greater {
+            return None;
+        }
+    }
+
+    let mut m = s;
+    let mut c = z.mod_pow(&q, p);
+    let mut t = a.mod_pow(&q, p);
+    let mut r = a.mod_pow(&q.add(&one).shr(1), p);
+
+    while !t.equals(&one) {
+        // Find the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t_squared = t.clone();
+        while !t_squared.equals(&one) {
+            t_squared = t_squared.mod_mul(&t_squared, p);
+            i += 1;
+            // For a prime p the order of t is below 2^m, so p is composite
+            if i == m {
+                return None;
+            }
+        }
+
+        let b = c.mod_pow(&one.shl((m - i - 1) as usize), p);
+        m = i;
+        c = b.mod_mul(&b, p);
+        t = t.mod_mul(&c, p);
+        r = r.mod_mul(&b, p);
+    }
+
+    checked(r)
 }
@@ -1,12 +1,14 @@
 use num_bigint::{BigUint, RandBigInt};
 use rand::thread_rng;
 
+use crate::bignum::{Backend, BigNum};
+
 pub fn generate_prime(bitsize: u32) -> BigUint {
     loop {
         let mut rng = thread_rng();
         let val: BigUint = rng.gen_biguint_range(
-            &((BigUint::new(vec![2]).pow(bitsize - 1)) + 1 as u64),
-            &((BigUint::new(vec![2]).pow(bitsize)) - 1 as u64),
+            &((BigUint::new(vec![2]).pow(bitsize - 1)) + 1u64),
+            &((BigUint::new(vec![2]).pow(bitsize)) - 1u64),
         );
 
         if is_prime(&val) {
@@ -26,35 +28,41 @@ pub fn is_prime(number: &BigUint) -> bool {
     if number <= &BigUint::from(3u64) {
         return true;
     };
+    if !number.bit(0) {
+        return false;
+    };
+
+    let number = Backend::from_biguint(number);
 
     // Find r such that n = 2^d * r + 1 for some r >= 1
-    let mut d: BigUint = number.clone() - 1 as u64;
-    while &d % 2 as u64 == BigUint::from(0u64) {
-        d /= 2 as u64;
-    }
+    let number_minus_one = number.sub(&Backend::from_u64(1));
+    let d = number_minus_one.shr(number_minus_one.trailing_zeros().unwrap() as usize);
 
     for _i in 0..k {
-        if miller_rabin(&d, number) == Primality::Composite {
+        if miller_rabin(&d, &number) == Primality::Composite {
             return false;
         }
     }
 
-    return true;
+    true
 }
 
-pub fn miller_rabin(d: &BigUint, number: &BigUint) -> Primality {
+pub fn miller_rabin<N: BigNum>(d: &N, number: &N) -> Primality {
     let mut d = d.clone();
+    let one = N::from_u64(1);
+    let number_minus_one = number.sub(&one);
 
     let mut rng = thread_rng();
     // Pick a random number in [2..n-2]
     // Corner cases make sure that n > 4
-    let random_int: BigUint =
-        rng.gen_biguint_range(&BigUint::from(2 as u64), &(number.clone() - (2 as u64)));
+    let random_int = N::from_biguint(
+        &rng.gen_biguint_range(&BigUint::from(2u64), &(number.to_biguint() - 2u64)),
+    );
 
     // Compute a^d % n
-    let mut x = random_int.modpow(&d, number);
+    let mut x = random_int.mod_pow(&d, number);
 
-    if x == BigUint::from(1u64) || x == number - BigUint::from(1u64) {
+    if x.equals(&one) || x.equals(&number_minus_one) {
         return Primality::ProbablyPrime;
     }
 
@@ -63,19 +71,19 @@ pub fn miller_rabin(d: &BigUint, number: &BigUint) -> Primality {
     // (i)   d does not reach n-1
     // (ii)  (x^2) % n is not 1
     // (iii) (x^2) % n is not n-1
-    while d != number - 1u64 {
-        x = (x.clone() * x.clone()) % number;
-        d *= 2u64;
+    while !d.equals(&number_minus_one) {
+        x = x.mod_mul(&x, number);
+        d = d.shl(1);
 
-        if x == BigUint::from(1u64) {
+        if x.equals(&one) {
             return Primality::Composite;
         };
-        if x == number - 1u64 {
+        if x.equals(&number_minus_one) {
             return Primality::ProbablyPrime;
         };
     }
 
-    return Primality::Composite;
+    Primality::Composite
 }
 
 #[derive(PartialEq)]
@@ -83,3 +91,21 @@ pub enum Primality {
     ProbablyPrime,
     Composite,
 }
+
+/// All primes strictly below `bound`, using the sieve of Eratosthenes.
+pub fn small_primes(bound: u64) -> Vec<u64> {
+    let bound = bound as usize;
+    let mut is_composite = vec![false; bound];
+    let mut primes = Vec::new();
+
+    for i in 2..bound {
+        if !is_composite[i] {
+            primes.push(i as u64);
+            for multiple in (i * i..bound).step_by(i) {
+                is_composite[multiple] = true;
+            }
+        }
+    }
+
+    primes
+}
@@ -0,0 +1,103 @@
+use std::sync::Mutex;
+
+use asn1_rs::{BigInt, BigUint};
+use num_bigint::RandBigInt;
+use rand::thread_rng;
+
+use crate::{
+    asn1::RSAPrivateKey,
+    math::{gcd, mod_inverse},
+};
+
+/// Per-key blinding state for private-key operations.
+///
+/// Before exponentiation the input is multiplied by `r^e` and afterwards the
+/// result by `r^-1`, so the value being exponentiated is unrelated to the
+/// attacker-chosen input. Instead of drawing a fresh `r` every time, the
+/// pair is squared after each use as suggested by Kocher, which is cheap and
+/// still gives a new unpredictable factor.
+///
+/// Like `MontgomeryCache`, the pair is stored with the modulus and public
+/// exponent it was made for, and a fresh one is drawn when the key fields
+/// have been changed since.
+#[derive(Default)]
+pub struct Blinding {
+    factors: Mutex<Option<BlindingFactors>>,
+    blind_exponent: bool,
+}
+
+struct BlindingFactors {
+    modulus: BigUint,
+    public_exponent: BigUint,
+    blind: BigInt,   // r^e mod n
+    unblind: BigInt, // r^-1 mod n
+}
+
+impl Blinding {
+    /// Returns the blinding pair for this operation and advances the cache.
+    pub(crate) fn next_factors(&self, key: &RSAPrivateKey) -> (BigInt, BigInt) {
+        let modulus = BigInt::from(key.modulus.clone());
+        let mut factors = self.factors.lock().unwrap();
+
+        let (blind, unblind) = match factors.take() {
+            Some(cached)
+                if cached.modulus == key.modulus
+                    && cached.public_exponent == key.public_exponent =>
+            {
+                (cached.blind, cached.unblind)
+            }
+            _ => Self::fresh_factors(key),
+        };
+
+        *factors = Some(BlindingFactors {
+            modulus: key.modulus.clone(),
+            public_exponent: key.public_exponent.clone(),
+            blind: &blind * &blind % &modulus,
+            unblind: &unblind * &unblind % &modulus,
+        });
+
+        (blind, unblind)
+    }
+
+    pub(crate) fn blind_exponent(&self) -> bool {
+        self.blind_exponent
+    }
+
+    fn fresh_factors(key: &RSAPrivateKey) -> (BigInt, BigInt) {
+        let one = BigUint::from(1u64);
+        let mut rng = thread_rng();
+
+        loop {
+            let r = rng.gen_biguint_range(&BigUint::from(2u64), &(&key.modulus - 1u64));
+            if gcd(&r, &key.modulus) != one {
+                continue;
+            }
+
+            let r = BigInt::from(r);
+            let modulus = BigInt::from(key.modulus.clone());
+            let blind = r.modpow(&BigInt::from(key.public_exponent.clone()), &modulus);
+            let unblind = mod_inverse(&r, &modulus).expect("r is coprime to n");
+
+            break (blind, unblind);
+        }
+    }
+}
+
+impl RSAPrivateKey {
+    /// Also blind the private exponent of each private-key operation.
+    ///
+    /// Every CRT exponent `d_i` is replaced with `d_i + k * (r_i - 1)` for a
+    /// fresh random 64-bit `k`, the per-prime form of `d + k * lambda(n)`, so
+    /// the exponent bits differ on every call. This costs 64 extra bits of
+    /// exponentiation per prime.
+    pub fn set_exponent_blinding(&mut self, enabled: bool) {
+        self.blinding.blind_exponent = enabled;
+    }
+
+    /// Drops the cached blinding factors so the next private-key operation
+    /// draws a fresh random `r`. Changing the modulus or public exponent of
+    /// a key in place already does this on the next operation.
+    pub fn reset_blinding(&self) {
+        *self.blinding.factors.lock().unwrap() = None;
+    }
+}
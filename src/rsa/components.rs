@@ -0,0 +1,139 @@
+use asn1_rs::BigUint;
+
+use crate::{
+    asn1::{OtherPrimeInfo, RSAPrivateKey},
+    math::{lcm, mod_inverse, recover_primes},
+    prime::is_prime,
+    rsa::{blinding::Blinding, precompute::MontgomeryCache},
+};
+
+pub type RSAKeyConstructionResult = Result<RSAPrivateKey, String>;
+
+impl RSAPrivateKey {
+    /// Rebuilds a full private key from only the modulus and exponents by
+    /// factoring `n` as described in SP 800-56B Appendix C.
+    ///
+    /// The supplied `d` is kept as-is, even if it was computed modulo phi(n)
+    /// rather than lambda(n), so the result matches what the legacy system
+    /// would compute.
+    pub fn from_components(
+        modulus: BigUint,
+        public_exponent: BigUint,
+        private_exponent: BigUint,
+    ) -> RSAKeyConstructionResult {
+        let (p, q) = recover_primes(&modulus, &public_exponent, &private_exponent)
+            .ok_or("could not factor the modulus with the given exponents")?;
+
+        // A modulus with more than two primes splits into a prime and a
+        // composite
+        if !is_prime(&p) || !is_prime(&q) {
+            return Err("factors must be prime".into());
+        }
+
+        // Factoring can succeed by luck even when d does not match e
+        let carmichael_totient = lcm(&(&p - 1u64), &(&q - 1u64));
+        if (&public_exponent * &private_exponent) % &carmichael_totient != BigUint::from(1u64) {
+            return Err("private exponent does not match the public exponent".into());
+        }
+
+        // Conventionally p is the larger prime
+        let (p, q) = if p > q { (p, q) } else { (q, p) };
+
+        Ok(Self::from_exponents(
+            vec![p, q],
+            public_exponent,
+            private_exponent,
+        ))
+    }
+
+    /// Builds a two-prime private key from its primes and public exponent.
+    pub fn from_primes(
+        p: BigUint,
+        q: BigUint,
+        public_exponent: BigUint,
+    ) -> RSAKeyConstructionResult {
+        Self::from_prime_factors(vec![p, q], public_exponent)
+    }
+
+    /// Builds a private key from two or more distinct primes, in the order
+    /// they should appear in the key, computing `d` modulo lambda(n).
+    pub fn from_prime_factors(
+        primes: Vec<BigUint>,
+        public_exponent: BigUint,
+    ) -> RSAKeyConstructionResult {
+        let one = BigUint::from(1u64);
+
+        if primes.len() < 2 {
+            return Err("at least two primes are required".into());
+        }
+        if primes.iter().any(|prime| prime <= &one) {
+            return Err("primes must be greater than 1".into());
+        }
+        if primes
+            .iter()
+            .enumerate()
+            .any(|(i, prime)| primes[..i].contains(prime))
+        {
+            return Err("primes must be distinct".into());
+        }
+        if primes.iter().any(|prime| !is_prime(prime)) {
+            return Err("factors must be prime".into());
+        }
+
+        let carmichael_totient = primes
+            .iter()
+            .map(|prime| prime - 1u64)
+            .fold(one.clone(), |acc, x| lcm(&acc, &x));
+
+        let d = mod_inverse(
+            &public_exponent.clone().into(),
+            &carmichael_totient.clone().into(),
+        )
+        .ok_or("public exponent is not invertible modulo lambda(n)")?;
+        let d = BigUint::try_from(d).expect("decryption constant is messed up");
+
+        Ok(Self::from_exponents(primes, public_exponent, d))
+    }
+
+    /// Fills in the modulus and CRT values for already consistent inputs.
+    fn from_exponents(primes: Vec<BigUint>, e: BigUint, d: BigUint) -> Self {
+        let p = &primes[0];
+        let q = &primes[1];
+
+        let mut other_prime_infos = Vec::new();
+        let mut product = p * q;
+        for prime in &primes[2..] {
+            other_prime_infos.push(OtherPrimeInfo {
+                prime: prime.clone(),
+                exponent: &d % (prime - 1u64),
+                coefficient: mod_inverse(&product.clone().into(), &prime.clone().into())
+                    .and_then(|coefficient| BigUint::try_from(coefficient).ok())
+                    .expect("coefficient calculation messed up."),
+            });
+            product *= prime;
+        }
+
+        let version = if other_prime_infos.is_empty() {
+            0u64
+        } else {
+            1u64
+        };
+
+        RSAPrivateKey {
+            version: BigUint::from(version),
+            modulus: product,
+            public_exponent: e,          // e
+            private_exponent: d.clone(), // d
+            prime1: p.clone(),           // p
+            prime2: q.clone(),           // q
+            exponent1: &d % (p - 1u64),  // d mod (p-1)
+            exponent2: &d % (q - 1u64),  // d mod (q-1)
+            coefficient: mod_inverse(&q.clone().into(), &p.clone().into())
+                .and_then(|coefficient| BigUint::try_from(coefficient).ok())
+                .expect("coefficient calculation messed up."), // (inverse of q) mod p
+            other_prime_infos,
+            blinding: Blinding::default(),
+            montgomery: MontgomeryCache::default(),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    asn1::RSAPrivateKey,
+    hash::{mgf1, Digest, Sha256},
+    rsa::{
+        fault::PrivateKeyError,
+        primitives::{i2osp, os2ip, rsadp},
+    },
+};
+use asn1_rs::BigInt;
+
+pub type RSADecryptionResult = Result<Vec<u8>, PrivateKeyError>;
+
+/// RSAES-PKCS1-v1_5 decryption from RFC 8017 section 7.2.2.
+///
+/// Every failure other than a detected fault is reported as the same
+/// "decryption error" so callers do not accidentally turn this into a
+/// padding oracle.
+pub fn rsaes_pkcs1_v1_5_decrypt(key: &RSAPrivateKey, ciphertext: &[u8]) -> RSADecryptionResult {
+    let k = key.modulus.to_bytes_be().len();
+    if k < 11 || ciphertext.len() != k {
+        return Err("decryption error".into());
+    }
+
+    let integer_ciphertext = os2ip(ciphertext);
+    if integer_ciphertext >= BigInt::from(key.modulus.clone()) {
+        return Err("decryption error".into());
+    }
+
+    let integer_message = rsadp(key, integer_ciphertext)?;
+    let em = i2osp(integer_message, k).map_err(|_| "decryption error")?;
+
+    // Scan the whole block regardless of where the separator is so the time
+    // taken does not depend on the padding length
+    let mut valid = em[0] == 0x00 && em[1] == 0x02;
+    let mut separator = 0;
+    for (i, &byte) in em.iter().enumerate().skip(2) {
+        if byte == 0x00 && separator == 0 {
+            separator = i;
+        }
+    }
+    valid &= separator >= 10;
+
+    if !valid {
+        return Err("decryption error".into());
+    }
+
+    Ok(em[separator + 1..].to_vec())
+}
+
+/// RSAES-OAEP decryption from RFC 8017 section 7.1.2 with SHA-256 and
+/// MGF1-SHA-256, failing with the same "decryption error" throughout unless
+/// a fault is detected.
+pub fn rsaes_oaep_decrypt(
+    key: &RSAPrivateKey,
+    ciphertext: &[u8],
+    label: &[u8],
+) -> RSADecryptionResult {
+    let k = key.modulus.to_bytes_be().len();
+    let h_len = Sha256::OUTPUT_SIZE;
+    if k < 2 * h_len + 2 || ciphertext.len() != k {
+        return Err("decryption error".into());
+    }
+
+    let integer_ciphertext = os2ip(ciphertext);
+    if integer_ciphertext >= BigInt::from(key.modulus.clone()) {
+        return Err("decryption error".into());
+    }
+
+    let integer_message = rsadp(key, integer_ciphertext)?;
+    let em = i2osp(integer_message, k).map_err(|_| "decryption error")?;
+
+    let (masked_seed, masked_db) = em[1..].split_at(h_len);
+    let seed: Vec<u8> = masked_seed
+        .iter()
+        .zip(mgf1::<Sha256>(masked_db, h_len))
+        .map(|(byte, mask)| byte ^ mask)
+        .collect();
+    let db: Vec<u8> = masked_db
+        .iter()
+        .zip(mgf1::<Sha256>(&seed, k - h_len - 1))
+        .map(|(byte, mask)| byte ^ mask)
+        .collect();
+
+    // Check lHash, Y and the separator without branching on any of them
+    let l_hash = Sha256::digest(label);
+    let mut difference = em[0];
+    for (a, b) in db.iter().zip(l_hash.iter()) {
+        difference |= a ^ b;
+    }
+    let mut separator = 0;
+    let mut invalid_padding = 0u8;
+    for (i, &byte) in db.iter().enumerate().skip(h_len) {
+        let searching = (separator == 0) as u8;
+        if byte == 0x01 && separator == 0 {
+            separator = i;
+        }
+        // Anything other than zeros before the 0x01 is malformed
+        invalid_padding |= searching & (byte != 0x00 && byte != 0x01) as u8;
+    }
+
+    if difference != 0 || invalid_padding != 0 || separator == 0 {
+        return Err("decryption error".into());
+    }
+
+    Ok(db[separator + 1..].to_vec())
+}
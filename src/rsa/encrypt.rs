@@ -1,45 +1,95 @@
-use crate::{asn1::RSAPublicKey, rsa::primitives::i2osp};
-use asn1_rs::{BigInt, BigUint};
-use rand::{thread_rng, RngCore};
+use crate::{
+    asn1::RSAPublicKey,
+    hash::{mgf1, Digest, Sha256},
+    rsa::primitives::i2osp,
+};
+use rand::{thread_rng, CryptoRng, RngCore};
 
 use super::primitives::{os2ip, rsaep};
 
 pub type RSAEncryptionResult = Result<Vec<u8>, String>;
 
-pub fn rsaes_pkcs1_v1_5_encrypt(recipient: RSAPublicKey, message: &[u8]) -> RSAEncryptionResult {
-    if message.len() <= recipient.modulus.to_bytes_be().len() - 11 {
-        let ps_length = recipient.modulus.to_bytes_be().len() - message.len() - 3;
+/// RSAES-PKCS1-v1_5 encryption from RFC 8017 section 7.2.1.
+pub fn rsaes_pkcs1_v1_5_encrypt(recipient: &RSAPublicKey, message: &[u8]) -> RSAEncryptionResult {
+    rsaes_pkcs1_v1_5_encrypt_with_rng(recipient, message, &mut thread_rng())
+}
 
-        let mut ps = Vec::with_capacity(ps_length);
-        thread_rng().fill_bytes(&mut ps);
+/// Same as `rsaes_pkcs1_v1_5_encrypt` but drawing the padding from `rng`.
+pub fn rsaes_pkcs1_v1_5_encrypt_with_rng<R: RngCore + CryptoRng>(
+    recipient: &RSAPublicKey,
+    message: &[u8],
+    rng: &mut R,
+) -> RSAEncryptionResult {
+    let k = recipient.modulus.to_bytes_be().len();
+    if k < 11 || message.len() > k - 11 {
+        return Err("message too long".into());
+    }
 
-        let mut em: Vec<u8> = Vec::new();
+    // EM = 0x00 || 0x02 || PS || 0x00 || M, with at least 8 bytes of PS
+    let mut em = vec![0u8; k];
+    em[1] = 0x02;
 
-        let mut message = Vec::from(message);
-        em.push(0x00);
-        em.push(0x02);
-        em.append(&mut ps);
-        em.push(0x00);
-        em.append(&mut message);
+    let ps_length = k - message.len() - 3;
+    let ps = &mut em[2..2 + ps_length];
+    rng.fill_bytes(ps);
+    for byte in ps.iter_mut() {
+        while *byte == 0x00 {
+            *byte = (rng.next_u32() & 0xff) as u8;
+        }
+    }
 
-        println!("{:?}", &em);
-        let integer_message = os2ip(&em);
+    em[k - message.len()..].copy_from_slice(message);
 
-        let integer_ciphertext = rsaep(&recipient, integer_message);
+    let integer_message = os2ip(&em);
+    let integer_ciphertext = rsaep(recipient, integer_message);
 
-        let cipher_text = i2osp(
-            integer_ciphertext,
-            recipient.modulus.clone().to_bytes_be().len(),
-        );
+    i2osp(integer_ciphertext, k)
+}
 
-        Ok(cipher_text)
-    } else {
-        Err("message too long".into())
-    }
+/// RSAES-OAEP encryption from RFC 8017 section 7.1.1 with SHA-256 and
+/// MGF1-SHA-256. `label` is usually empty.
+pub fn rsaes_oaep_encrypt(
+    recipient: &RSAPublicKey,
+    message: &[u8],
+    label: &[u8],
+) -> RSAEncryptionResult {
+    rsaes_oaep_encrypt_with_rng(recipient, message, label, &mut thread_rng())
 }
 
-/*
-fn rsaes_oaep_encrypt(recipient: RSAPublicKey, message: &[u8], label: &[u8]) {
+/// Same as `rsaes_oaep_encrypt` but drawing the seed from `rng`.
+pub fn rsaes_oaep_encrypt_with_rng<R: RngCore + CryptoRng>(
+    recipient: &RSAPublicKey,
+    message: &[u8],
+    label: &[u8],
+    rng: &mut R,
+) -> RSAEncryptionResult {
+    let k = recipient.modulus.to_bytes_be().len();
+    let h_len = Sha256::OUTPUT_SIZE;
+    if k < 2 * h_len + 2 || message.len() > k - 2 * h_len - 2 {
+        return Err("message too long".into());
+    }
+
+    // DB = lHash || PS || 0x01 || M
+    let mut db = Sha256::digest(label);
+    db.resize(k - message.len() - h_len - 2, 0x00);
+    db.push(0x01);
+    db.extend_from_slice(message);
+
+    let mut seed = vec![0u8; h_len];
+    rng.fill_bytes(&mut seed);
+
+    for (byte, mask) in db.iter_mut().zip(mgf1::<Sha256>(&seed, k - h_len - 1)) {
+        *byte ^= mask;
+    }
+    for (byte, mask) in seed.iter_mut().zip(mgf1::<Sha256>(&db, h_len)) {
+        *byte ^= mask;
+    }
+
+    // EM = 0x00 || maskedSeed || maskedDB
+    let em = [&[0x00][..], &seed, &db].concat();
+
+    let integer_message = os2ip(&em);
+    let integer_ciphertext = rsaep(recipient, integer_message);
 
+    i2osp(integer_ciphertext, k)
 }
- */
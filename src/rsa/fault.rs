@@ -0,0 +1,72 @@
+use std::{error::Error, fmt};
+
+/// Returned in place of the result when a private-key operation fails its
+/// consistency check.
+///
+/// With CRT a fault in just one of the half exponentiations gives a result
+/// that is correct modulo one prime but not the other, and a single such
+/// output is enough to factor the modulus (the Bellcore attack). Every
+/// private-key operation is therefore checked by re-applying the public
+/// exponent, and nothing is released when the check fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultDetected;
+
+impl fmt::Display for FaultDetected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "private key operation fault detected")
+    }
+}
+
+impl Error for FaultDetected {}
+
+impl From<FaultDetected> for String {
+    fn from(fault: FaultDetected) -> Self {
+        fault.to_string()
+    }
+}
+
+/// Error of the private-key operations in `hazmat`, `decrypt` and `sign`.
+///
+/// A `Fault` means the key or the hardware misbehaved and nothing was
+/// released, while `Malformed` carries the usual message for input or keys
+/// that were rejected up front.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivateKeyError {
+    Fault,
+    Malformed(String),
+}
+
+impl fmt::Display for PrivateKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fault => write!(f, "{}", FaultDetected),
+            Self::Malformed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for PrivateKeyError {}
+
+impl From<FaultDetected> for PrivateKeyError {
+    fn from(_: FaultDetected) -> Self {
+        Self::Fault
+    }
+}
+
+impl From<String> for PrivateKeyError {
+    fn from(message: String) -> Self {
+        Self::Malformed(message)
+    }
+}
+
+impl From<&str> for PrivateKeyError {
+    fn from(message: &str) -> Self {
+        Self::Malformed(message.into())
+    }
+}
+
+impl From<PrivateKeyError> for String {
+    fn from(error: PrivateKeyError) -> Self {
+        error.to_string()
+    }
+}
@@ -1,56 +1,88 @@
-use std::{thread, time::Duration};
+use std::thread;
 
 use asn1_rs::BigUint;
 use log::debug;
 
-use crate::{
-    asn1::{RSAPrivateKey, RSAPublicKey},
-    math::{lcm, mod_inverse},
-    prime::generate_prime,
-};
+use crate::{asn1::RSAPrivateKey, math::lcm, prime::generate_prime};
+
+pub type RSAKeyGenerationResult = Result<RSAPrivateKey, String>;
+
+/// Largest number of primes accepted by `generate_multiprime_keypair`.
+pub const MAX_PRIME_COUNT: usize = 5;
 
 pub fn generate_keypair(bitsize: u32) -> RSAPrivateKey {
-    let bits = bitsize;
-
-    let (p, q, n, carmichael_totient, e) = loop {
-        let mut p_handle = thread::spawn(move || {
-            let p = generate_prime(bits / 2);
-            log::debug!("Found p: {}", p);
-            p
-        });
-
-        let mut q_handle = thread::spawn(move || {
-            let q = generate_prime(bits / 2);
-            log::debug!("Found q: {}", q);
-            q
-        });
-
-        let mut p = BigUint::from(0u64);
-        let mut q = BigUint::from(0u64);
-
-        loop {
-            if p_handle.is_finished() && q_handle.is_finished() {
-                p = p_handle.join().unwrap();
-                q = q_handle.join().unwrap();
-                break;
-            }
-
-            thread::sleep(Duration::from_millis(500));
+    generate_multiprime_keypair(bitsize, 2).expect("key size is too small")
+}
+
+/// Generates a key whose modulus is the product of `prime_count` primes.
+///
+/// Two primes give a regular version 0 key, anything up to `MAX_PRIME_COUNT`
+/// gives a version 1 key carrying the extra primes in `other_prime_infos`.
+pub fn generate_multiprime_keypair(bitsize: u32, prime_count: usize) -> RSAKeyGenerationResult {
+    if !(2..=MAX_PRIME_COUNT).contains(&prime_count) {
+        return Err(format!(
+            "prime count must be between 2 and {}",
+            MAX_PRIME_COUNT
+        ));
+    }
+
+    let count = prime_count as u32;
+    if bitsize / count < 16 {
+        return Err("key size too small for the requested number of primes".into());
+    }
+
+    let (primes, e) = loop {
+        // Spread the bits evenly, giving any remainder to the last prime
+        let handles: Vec<_> = (0..count)
+            .map(|i| {
+                let bits = if i == count - 1 {
+                    bitsize - (count - 1) * (bitsize / count)
+                } else {
+                    bitsize / count
+                };
+
+                thread::spawn(move || {
+                    let prime = generate_prime(bits);
+                    log::debug!("Found prime {}: {}", i + 1, prime);
+                    prime
+                })
+            })
+            .collect();
+
+        let primes: Vec<BigUint> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        if primes
+            .iter()
+            .enumerate()
+            .any(|(i, prime)| primes[..i].contains(prime))
+        {
+            debug!("Oh noez duplicate primes regenerating keys.");
+            continue;
         }
 
-        let n = &p * &q;
+        let n = primes.iter().product::<BigUint>();
+        if n.bits() != u64::from(bitsize) {
+            debug!("Oh noez modulus is {} bits regenerating keys.", n.bits());
+            continue;
+        }
         log::debug!("Computed n: {}", n);
 
-        let carmichael_totient = lcm(&(p.clone() - 1u64), &(q.clone() - 1u64));
+        let carmichael_totient = primes
+            .iter()
+            .map(|prime| prime - 1u64)
+            .fold(BigUint::from(1u64), |acc, x| lcm(&acc, &x));
 
         log::debug!(
-            "Computed carmichael totient of p - 1 and n - 1: {}",
+            "Computed carmichael totient of the primes: {}",
             carmichael_totient
         );
 
         let e = BigUint::from(65537u64);
         if &carmichael_totient % &e != BigUint::from(0u64) {
-            break (p, q, n, carmichael_totient, e);
+            break (primes, e);
         } else {
             debug!("Oh noez vulnerable e regenerating keys.");
         }
@@ -58,26 +90,10 @@ pub fn generate_keypair(bitsize: u32) -> RSAPrivateKey {
 
     log::debug!("Found e {}", e);
 
-    let d = BigUint::try_from(mod_inverse(
-        &e.clone().into(),
-        &carmichael_totient.clone().into(),
-    ))
-    .expect("decryption constant is messed up");
-
-    log::debug!("Found d {}", d);
-
-    let private_key = RSAPrivateKey {
-        version: BigUint::from(0u64),
-        modulus: n.clone(),
-        public_exponent: e.clone(),                // e
-        private_exponent: d.clone(),               // d
-        prime1: p.clone(),                         // p
-        prime2: q.clone(),                         // q
-        exponent1: d.clone() % (p.clone() - 1u64), // d mod (p-1)
-        exponent2: d % (q.clone() - 1u64),         // d mod (q-1)
-        coefficient: BigUint::try_from(mod_inverse(&(q.clone()).into(), &(p.clone()).into()))
-            .expect("coefficient calculation messed up."), // (inverse of q) mod p
-    };
+    let private_key =
+        RSAPrivateKey::from_prime_factors(primes, e).expect("decryption constant is messed up");
+
+    log::debug!("Found d {}", private_key.private_exponent);
 
-    private_key
+    Ok(private_key)
 }
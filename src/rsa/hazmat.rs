@@ -0,0 +1,116 @@
+//! Raw "textbook" RSA without any padding.
+//!
+//! These are the RFC 8017 section 5 primitives exposed for teaching and for
+//! prototyping protocols. Unpadded RSA is deterministic and malleable, so
+//! nothing here is safe to use on its own for encrypting or signing real
+//! data; use `rsa::encrypt` and friends for that.
+//!
+//! Every function checks that its input representative lies in `[0, n)` as
+//! required by the RFC, and the byte string variants read and write octet
+//! strings of exactly the modulus length `k`. The private-key functions
+//! fail with `PrivateKeyError::Fault` rather than return a result that does
+//! not verify under the public key.
+
+use asn1_rs::BigInt;
+
+use crate::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    rsa::{fault::PrivateKeyError, primitives},
+};
+
+pub use crate::rsa::primitives::{i2osp, os2ip};
+
+pub type HazmatResult<T> = Result<T, PrivateKeyError>;
+
+fn check_range(representative: &BigInt, modulus: &BigInt, error: &str) -> HazmatResult<()> {
+    if representative < &BigInt::from(0u64) || representative >= modulus {
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+fn modulus_length(modulus: &BigInt) -> usize {
+    modulus.to_bytes_be().1.len()
+}
+
+/// RSAEP: `c = m^e mod n`.
+pub fn rsaep(recipient: &RSAPublicKey, integer_message: &BigInt) -> HazmatResult<BigInt> {
+    let modulus = BigInt::from(recipient.modulus.clone());
+    check_range(
+        integer_message,
+        &modulus,
+        "message representative out of range",
+    )?;
+
+    Ok(primitives::rsaep(recipient, integer_message.clone()))
+}
+
+/// RSADP: `m = c^d mod n`, computed with the CRT values of the key.
+pub fn rsadp(key: &RSAPrivateKey, integer_ciphertext: &BigInt) -> HazmatResult<BigInt> {
+    let modulus = BigInt::from(key.modulus.clone());
+    check_range(
+        integer_ciphertext,
+        &modulus,
+        "ciphertext representative out of range",
+    )?;
+
+    Ok(primitives::rsadp(key, integer_ciphertext.clone())?)
+}
+
+/// RSASP1: `s = m^d mod n`, computed with the CRT values of the key.
+pub fn rsasp1(key: &RSAPrivateKey, integer_message: &BigInt) -> HazmatResult<BigInt> {
+    let modulus = BigInt::from(key.modulus.clone());
+    check_range(
+        integer_message,
+        &modulus,
+        "message representative out of range",
+    )?;
+
+    Ok(primitives::rsasp1(key, integer_message.clone())?)
+}
+
+/// RSAVP1: `m = s^e mod n`.
+pub fn rsavp1(signer: &RSAPublicKey, integer_signature: &BigInt) -> HazmatResult<BigInt> {
+    let modulus = BigInt::from(signer.modulus.clone());
+    check_range(
+        integer_signature,
+        &modulus,
+        "signature representative out of range",
+    )?;
+
+    Ok(primitives::rsavp1(signer, integer_signature.clone()))
+}
+
+/// RSAEP on octet strings, returning a `k` byte ciphertext.
+pub fn rsaep_octets(recipient: &RSAPublicKey, message: &[u8]) -> HazmatResult<Vec<u8>> {
+    let modulus = BigInt::from(recipient.modulus.clone());
+    let integer_ciphertext = rsaep(recipient, &os2ip(message))?;
+
+    Ok(i2osp(integer_ciphertext, modulus_length(&modulus))?)
+}
+
+/// RSADP on octet strings, returning a `k` byte message.
+pub fn rsadp_octets(key: &RSAPrivateKey, ciphertext: &[u8]) -> HazmatResult<Vec<u8>> {
+    let modulus = BigInt::from(key.modulus.clone());
+    let integer_message = rsadp(key, &os2ip(ciphertext))?;
+
+    Ok(i2osp(integer_message, modulus_length(&modulus))?)
+}
+
+/// RSASP1 on octet strings, returning a `k` byte signature.
+pub fn rsasp1_octets(key: &RSAPrivateKey, message: &[u8]) -> HazmatResult<Vec<u8>> {
+    let modulus = BigInt::from(key.modulus.clone());
+    let integer_signature = rsasp1(key, &os2ip(message))?;
+
+    Ok(i2osp(integer_signature, modulus_length(&modulus))?)
+}
+
+/// RSAVP1 on octet strings, returning the `k` byte message the signature
+/// opens to.
+pub fn rsavp1_octets(signer: &RSAPublicKey, signature: &[u8]) -> HazmatResult<Vec<u8>> {
+    let modulus = BigInt::from(signer.modulus.clone());
+    let integer_message = rsavp1(signer, &os2ip(signature))?;
+
+    Ok(i2osp(integer_message, modulus_length(&modulus))?)
+}
@@ -0,0 +1,133 @@
+//! RSA-KEM from ISO 18033-2 and RFC 5990.
+//!
+//! `encapsulate` encrypts a random integer `z` below the modulus and runs
+//! it through a KDF to get the shared secret, so unlike RSAES there is no
+//! padding to get wrong. The KDF is KDF2 from ISO 18033-2 or HKDF from
+//! RFC 5869, as used by CMS `KEMRecipientInfo` (RFC 9629 and RFC 9690).
+
+use asn1_rs::BigInt;
+use num_bigint::RandBigInt;
+use rand::{thread_rng, CryptoRng, RngCore};
+
+use crate::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    hash::{HashAlgorithm, Sha1, Sha224, Sha256, Sha384, Sha512},
+    kdf::hkdf,
+    rsa::primitives::{i2osp, os2ip, rsadp, rsaep},
+};
+
+pub type KemResult<T> = Result<T, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    /// KDF2 from ISO 18033-2, the ANSI X9.63 KDF: the hash of the secret,
+    /// a 32-bit counter from 1 and the info, repeated until long enough.
+    Kdf2(HashAlgorithm),
+    /// HKDF from RFC 5869 with an empty salt.
+    Hkdf(HashAlgorithm),
+}
+
+impl Kdf {
+    /// Derives `length` bytes from `secret`, bound to `info`.
+    pub fn derive(&self, secret: &[u8], info: &[u8], length: usize) -> KemResult<Vec<u8>> {
+        match *self {
+            Kdf::Kdf2(hash) => {
+                let blocks = length.div_ceil(hash.output_size());
+                if blocks > u32::MAX as usize {
+                    return Err("KDF2 output too long".into());
+                }
+
+                let mut output = Vec::with_capacity(blocks * hash.output_size());
+                for counter in 1..=blocks as u32 {
+                    output.extend_from_slice(&hash.digest_parts(&[
+                        secret,
+                        &counter.to_be_bytes(),
+                        info,
+                    ]));
+                }
+                output.truncate(length);
+                Ok(output)
+            }
+            Kdf::Hkdf(hash) => match hash {
+                HashAlgorithm::Sha1 => hkdf::<Sha1>(&[], secret, info, length),
+                HashAlgorithm::Sha224 => hkdf::<Sha224>(&[], secret, info, length),
+                HashAlgorithm::Sha256 => hkdf::<Sha256>(&[], secret, info, length),
+                HashAlgorithm::Sha384 => hkdf::<Sha384>(&[], secret, info, length),
+                HashAlgorithm::Sha512 => hkdf::<Sha512>(&[], secret, info, length),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KemParameters {
+    pub kdf: Kdf,
+    /// Length of the shared secret in bytes
+    pub key_length: usize,
+    /// KDF2 OtherInfo or HKDF info, such as the DER CMSORIforKEMOtherInfo
+    /// of a `KEMRecipientInfo`
+    pub info: Vec<u8>,
+}
+
+impl Default for KemParameters {
+    /// HKDF with SHA-256 deriving a 256-bit key, without info.
+    fn default() -> Self {
+        Self {
+            kdf: Kdf::Hkdf(HashAlgorithm::Sha256),
+            key_length: 32,
+            info: Vec::new(),
+        }
+    }
+}
+
+/// Generates a shared secret for `recipient`, returning it with the
+/// ciphertext that `decapsulate` recovers it from.
+pub fn encapsulate(
+    recipient: &RSAPublicKey,
+    parameters: &KemParameters,
+) -> KemResult<(Vec<u8>, Vec<u8>)> {
+    encapsulate_with_rng(recipient, parameters, &mut thread_rng())
+}
+
+/// Same as `encapsulate` but drawing `z` from `rng`.
+pub fn encapsulate_with_rng<R: RngCore + CryptoRng>(
+    recipient: &RSAPublicKey,
+    parameters: &KemParameters,
+    rng: &mut R,
+) -> KemResult<(Vec<u8>, Vec<u8>)> {
+    let k = recipient.modulus.to_bytes_be().len();
+
+    let z = BigInt::from(rng.gen_biguint_below(&recipient.modulus));
+    let secret = i2osp(z.clone(), k)?;
+    let ciphertext = i2osp(rsaep(recipient, z), k)?;
+
+    let shared_secret = parameters
+        .kdf
+        .derive(&secret, &parameters.info, parameters.key_length)?;
+    Ok((shared_secret, ciphertext))
+}
+
+/// Recovers the shared secret from an `encapsulate` ciphertext. Every
+/// failure is the same "decapsulation error".
+pub fn decapsulate(
+    key: &RSAPrivateKey,
+    ciphertext: &[u8],
+    parameters: &KemParameters,
+) -> KemResult<Vec<u8>> {
+    let k = key.modulus.to_bytes_be().len();
+    if ciphertext.len() != k {
+        return Err("decapsulation error".into());
+    }
+
+    let integer_ciphertext = os2ip(ciphertext);
+    if integer_ciphertext >= BigInt::from(key.modulus.clone()) {
+        return Err("decapsulation error".into());
+    }
+
+    let z = rsadp(key, integer_ciphertext).map_err(|_| "decapsulation error")?;
+    let secret = i2osp(z, k).map_err(|_| "decapsulation error")?;
+
+    parameters
+        .kdf
+        .derive(&secret, &parameters.info, parameters.key_length)
+}
@@ -1,3 +1,13 @@
+pub mod blinding;
+pub mod components;
+pub mod decrypt;
 pub mod encrypt;
+pub mod fault;
 pub mod gen_keypair;
-pub mod primitives;
+pub mod hazmat;
+pub mod kem;
+pub mod precompute;
+pub(crate) mod primitives;
+pub mod sign;
+pub mod validate;
+pub mod verify;
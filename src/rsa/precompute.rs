@@ -0,0 +1,81 @@
+use std::{borrow::Cow, sync::OnceLock};
+
+use asn1_rs::BigUint;
+
+use crate::{
+    arith::MontgomeryContext,
+    asn1::{RSAPrivateKey, RSAPublicKey},
+};
+
+/// Montgomery parameters of a key, built on first use and then kept for the
+/// lifetime of the key.
+///
+/// The key fields are public and may be changed after the cache is filled,
+/// so every lookup checks the cached moduli against the current ones and
+/// falls back to a fresh, uncached context when they differ. A `None`
+/// context means the modulus is even, which no valid key has; callers then
+/// fall back to `num_bigint`.
+#[derive(Default)]
+pub struct MontgomeryCache {
+    modulus: OnceLock<Option<MontgomeryContext>>,
+    primes: OnceLock<Vec<Option<MontgomeryContext>>>,
+}
+
+fn matches(context: &Option<MontgomeryContext>, modulus: &BigUint) -> bool {
+    match context {
+        Some(context) => context.modulus() == modulus,
+        None => modulus <= &BigUint::from(1u64) || !modulus.bit(0),
+    }
+}
+
+impl MontgomeryCache {
+    fn modulus(&self, modulus: &BigUint) -> Cow<'_, Option<MontgomeryContext>> {
+        let context = self.modulus.get_or_init(|| MontgomeryContext::new(modulus));
+
+        if matches(context, modulus) {
+            Cow::Borrowed(context)
+        } else {
+            Cow::Owned(MontgomeryContext::new(modulus))
+        }
+    }
+
+    fn primes(&self, primes: &[&BigUint]) -> Cow<'_, [Option<MontgomeryContext>]> {
+        let build = || {
+            primes
+                .iter()
+                .map(|prime| MontgomeryContext::new(prime))
+                .collect::<Vec<_>>()
+        };
+        let contexts = self.primes.get_or_init(build);
+
+        let current = contexts.len() == primes.len()
+            && contexts
+                .iter()
+                .zip(primes)
+                .all(|(context, prime)| matches(context, prime));
+        if current {
+            Cow::Borrowed(contexts)
+        } else {
+            Cow::Owned(build())
+        }
+    }
+}
+
+impl RSAPublicKey {
+    /// Montgomery context modulo `n`, or `None` if `n` is even.
+    pub(crate) fn modulus_context(&self) -> Cow<'_, Option<MontgomeryContext>> {
+        self.montgomery.modulus(&self.modulus)
+    }
+}
+
+impl RSAPrivateKey {
+    /// Montgomery context modulo `n`, or `None` if `n` is even.
+    pub(crate) fn modulus_context(&self) -> Cow<'_, Option<MontgomeryContext>> {
+        self.montgomery.modulus(&self.modulus)
+    }
+
+    /// Montgomery contexts modulo each prime, in the order of `primes()`.
+    pub(crate) fn prime_contexts(&self) -> Cow<'_, [Option<MontgomeryContext>]> {
+        self.montgomery.primes(&self.primes())
+    }
+}
@@ -1,37 +1,139 @@
-use asn1_rs::BigInt;
+use asn1_rs::{BigInt, BigUint, Sign};
+use rand::{thread_rng, RngCore};
 
-use crate::asn1::RSAPublicKey;
+use crate::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    bignum::{Backend, BigNum},
+    rsa::fault::FaultDetected,
+};
 
-pub fn os2ip(message: &[u8]) -> BigInt {
-    let mut result = BigInt::from(0u32);
-    for i in 1..message.len() {
-        result += BigInt::from(message[message.len() - i].to_owned())
-            * BigInt::from(256u32).pow((message.len() - i).try_into().unwrap())
+/// OS2IP from RFC 8017 section 4.2, interpreting `octets` as a big-endian
+/// unsigned integer.
+pub fn os2ip(octets: &[u8]) -> BigInt {
+    BigInt::from_bytes_be(Sign::Plus, octets)
+}
+
+pub fn rsaep(recipient: &RSAPublicKey, integer_message: BigInt) -> BigInt {
+    let message = integer_message
+        .to_biguint()
+        .expect("message representative is non-negative");
+
+    let ciphertext = match recipient.modulus_context().as_ref() {
+        Some(context) => context.pow_vartime(&message, &recipient.public_exponent),
+        None => message.modpow(&recipient.public_exponent, &recipient.modulus),
+    };
+    BigInt::from(ciphertext)
+}
+
+/// RSADP from RFC 8017 section 5.1.2 using the CRT representation of the key,
+/// including the additional primes of multi-prime keys.
+///
+/// The ciphertext is blinded with the key's cached blinding factors and, if
+/// enabled on the key, the CRT exponents are blinded too. The result is
+/// checked against the public exponent before it is unblinded.
+pub fn rsadp(key: &RSAPrivateKey, integer_ciphertext: BigInt) -> Result<BigInt, FaultDetected> {
+    let n = Backend::from_biguint(&key.modulus);
+    let (blind, unblind) = key.blinding.next_factors(key);
+
+    let blinded_ciphertext = Backend::from_biguint(integer_ciphertext.magnitude())
+        .mod_mul(&Backend::from_biguint(blind.magnitude()), &n)
+        .to_biguint();
+    let blinded_message = rsadp_crt(key, &blinded_ciphertext);
+
+    let e = &key.public_exponent;
+    let check = match key.modulus_context().as_ref() {
+        Some(context) => context.pow_vartime(&blinded_message.to_biguint(), e),
+        None => blinded_message.to_biguint().modpow(e, &key.modulus),
+    };
+    if check != blinded_ciphertext {
+        return Err(FaultDetected);
     }
 
-    result
+    let integer_message = blinded_message.mod_mul(&Backend::from_biguint(unblind.magnitude()), &n);
+    Ok(BigInt::from(integer_message.to_biguint()))
 }
 
-pub fn rsaep(recipient: &RSAPublicKey, integer_message: BigInt) -> BigInt {
-    let integer_ciphertext = integer_message.modpow(
-        &BigInt::from(recipient.public_exponent.clone()),
-        &BigInt::from(recipient.modulus.clone()),
-    );
+fn rsadp_crt(key: &RSAPrivateKey, ciphertext: &BigUint) -> Backend {
+    let mut rng = thread_rng();
+    let blind_exponent = key.blinding.blind_exponent();
+
+    // c^(d_i) mod r_i in constant time, with d_i + k * (r_i - 1) for random k
+    // when exponent blinding is enabled. The blinded exponent is up to 64
+    // bits wider than the prime, so it always gets one extra limb.
+    let contexts = key.prime_contexts();
+    let mut private_pow = |index: usize, exponent: &BigUint, prime: &BigUint| {
+        let exponent = if blind_exponent {
+            exponent + BigUint::from(rng.next_u64()) * (prime - 1u64)
+        } else {
+            exponent.clone()
+        };
+
+        // Only an invalid key with an even prime has no context
+        let result = match &contexts[index] {
+            Some(context) if blind_exponent => {
+                context.pow_with_width(ciphertext, &exponent, context.width() + 1)
+            }
+            Some(context) => context.pow(ciphertext, &exponent),
+            None => ciphertext.modpow(&exponent, prime),
+        };
+        Backend::from_biguint(&result)
+    };
 
-    integer_ciphertext
+    let m_1 = private_pow(0, &key.exponent1, &key.prime1);
+    let m_2 = private_pow(1, &key.exponent2, &key.prime2);
+
+    let p = Backend::from_biguint(&key.prime1);
+    let q = Backend::from_biguint(&key.prime2);
+
+    // Garner's recombination of the first two primes
+    let h = m_1
+        .mod_sub(&m_2, &p)
+        .mod_mul(&Backend::from_biguint(&key.coefficient), &p);
+    let mut integer_message = m_2.add(&q.mul(&h));
+
+    // Fold in each additional prime r_i with its coefficient t_i
+    let mut r = p.mul(&q);
+    for (i, info) in key.other_prime_infos.iter().enumerate() {
+        let r_i = Backend::from_biguint(&info.prime);
+        let m_i = private_pow(i + 2, &info.exponent, &info.prime);
+        let h = m_i
+            .mod_sub(&integer_message, &r_i)
+            .mod_mul(&Backend::from_biguint(&info.coefficient), &r_i);
+        integer_message = integer_message.add(&r.mul(&h));
+        r = r.mul(&r_i);
+    }
+
+    integer_message
 }
 
-pub fn i2osp(message: BigInt, message_length: usize) -> Vec<u8> {
-    let mut message = message.clone();
-    let mut result: Vec<u8> = Vec::new();
-    for i in 1..=message_length {
-        let message_section =
-            message.clone() / BigInt::from(256i32).pow((message_length - i).try_into().unwrap());
-        message =
-            message.clone() % BigInt::from(256i32).pow((message_length - i).try_into().unwrap());
-        result.push(message_section.try_into().unwrap());
+/// RSASP1 from RFC 8017 section 5.2.1, which is RSADP under another name.
+pub fn rsasp1(key: &RSAPrivateKey, integer_message: BigInt) -> Result<BigInt, FaultDetected> {
+    rsadp(key, integer_message)
+}
+
+/// RSAVP1 from RFC 8017 section 5.2.2, which is RSAEP under another name.
+pub fn rsavp1(signer: &RSAPublicKey, integer_signature: BigInt) -> BigInt {
+    rsaep(signer, integer_signature)
+}
+
+/// I2OSP from RFC 8017 section 4.1, encoding `integer` as exactly `length`
+/// big-endian octets.
+///
+/// Fails with "integer too large" when `integer >= 256^length`, and for
+/// negative integers which have no octet string representation.
+pub fn i2osp(integer: BigInt, length: usize) -> Result<Vec<u8>, String> {
+    let (sign, bytes) = integer.to_bytes_be();
+    if sign == Sign::Minus {
+        return Err("integer is negative".into());
+    }
+
+    // to_bytes_be gives [0] for zero, which still fits in zero octets
+    let bytes: &[u8] = if sign == Sign::NoSign { &[] } else { &bytes };
+    if bytes.len() > length {
+        return Err("integer too large".into());
     }
 
-    result.reverse();
-    result
+    let mut result = vec![0u8; length - bytes.len()];
+    result.extend_from_slice(bytes);
+    Ok(result)
 }
@@ -0,0 +1,46 @@
+use crate::{
+    asn1::RSAPrivateKey,
+    hash::{Digest, Sha256},
+    rsa::{
+        fault::PrivateKeyError,
+        primitives::{i2osp, os2ip, rsasp1},
+    },
+};
+
+pub type RSASignatureResult = Result<Vec<u8>, PrivateKeyError>;
+
+/// DER encoding of the SHA-256 DigestInfo up to the digest itself, from
+/// RFC 8017 section 9.2 note 1.
+const SHA256_DIGEST_INFO_PREFIX: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// EMSA-PKCS1-v1_5 encoding from RFC 8017 section 9.2 with SHA-256.
+pub(crate) fn emsa_pkcs1_v1_5_encode(message: &[u8], em_len: usize) -> Result<Vec<u8>, String> {
+    let t = [&SHA256_DIGEST_INFO_PREFIX[..], &Sha256::digest(message)].concat();
+    if em_len < t.len() + 11 {
+        return Err("intended encoded message length too short".into());
+    }
+
+    // EM = 0x00 || 0x01 || PS || 0x00 || T, with PS all 0xff
+    let mut em = vec![0xff; em_len];
+    em[0] = 0x00;
+    em[1] = 0x01;
+    em[em_len - t.len() - 1] = 0x00;
+    em[em_len - t.len()..].copy_from_slice(&t);
+
+    Ok(em)
+}
+
+/// RSASSA-PKCS1-v1_5 signature generation from RFC 8017 section 8.2.1, using
+/// SHA-256 as the hash function.
+pub fn rsassa_pkcs1_v1_5_sign(key: &RSAPrivateKey, message: &[u8]) -> RSASignatureResult {
+    let k = key.modulus.to_bytes_be().len();
+    let em = emsa_pkcs1_v1_5_encode(message, k).map_err(|_| "RSA modulus too short")?;
+
+    let integer_message = os2ip(&em);
+    let integer_signature = rsasp1(key, integer_message)?;
+
+    Ok(i2osp(integer_signature, k)?)
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+
+use asn1_rs::{BigInt, BigUint};
+use num_bigint::RandBigInt;
+use rand::thread_rng;
+use serde::Serialize;
+
+use crate::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    math::{gcd, is_perfect_power, lcm},
+    prime::{is_prime, small_primes},
+    rsa::primitives::{rsadp, rsaep, rsasp1, rsavp1},
+};
+
+/// Outcome of a key validation, listing every check that did not pass.
+#[derive(Debug, Serialize)]
+pub struct ValidationReport<T> {
+    pub failures: Vec<T>,
+}
+
+impl<T> ValidationReport<T> {
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ValidationReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.failures.is_empty() {
+            return write!(f, "all checks passed");
+        }
+
+        for failure in &self.failures {
+            writeln!(f, "{}", failure)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A failed check from the SP 800-56B section 6.4 key-pair validation.
+///
+/// Primes are numbered from 1 in the order they appear in the key, so 1 is
+/// `prime1`, 2 is `prime2` and 3 onwards are the entries of
+/// `other_prime_infos`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "check")]
+pub enum PrivateKeyCheck {
+    Version,
+    PrimeNotPrime { index: usize },
+    DuplicatePrime { index: usize },
+    ModulusMismatch,
+    PublicExponentEven,
+    PublicExponentOutOfRange,
+    PublicExponentNotCoprime,
+    PrivateExponentOutOfRange,
+    PrivateExponentNotInverse,
+    CrtExponent { index: usize },
+    CrtCoefficient { index: usize },
+    PairwiseEncryption,
+    PairwiseSignature,
+}
+
+impl fmt::Display for PrivateKeyCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version => write!(f, "version does not match the number of primes"),
+            Self::PrimeNotPrime { index } => write!(f, "prime {} is not prime", index),
+            Self::DuplicatePrime { index } => {
+                write!(f, "prime {} repeats an earlier prime", index)
+            }
+            Self::ModulusMismatch => write!(f, "modulus is not the product of the primes"),
+            Self::PublicExponentEven => write!(f, "public exponent is even"),
+            Self::PublicExponentOutOfRange => {
+                write!(f, "public exponent is outside of (2^16, 2^256)")
+            }
+            Self::PublicExponentNotCoprime => {
+                write!(f, "public exponent is not coprime to lambda(n)")
+            }
+            Self::PrivateExponentOutOfRange => {
+                write!(f, "private exponent is outside of (2^(nBits/2), lambda(n))")
+            }
+            Self::PrivateExponentNotInverse => {
+                write!(f, "d * e is not congruent to 1 mod lambda(n)")
+            }
+            Self::CrtExponent { index } => {
+                write!(
+                    f,
+                    "CRT exponent for prime {} is not d mod (prime - 1)",
+                    index
+                )
+            }
+            Self::CrtCoefficient { index } => {
+                write!(f, "CRT coefficient for prime {} is incorrect", index)
+            }
+            Self::PairwiseEncryption => write!(f, "pairwise encrypt/decrypt test failed"),
+            Self::PairwiseSignature => write!(f, "pairwise sign/verify test failed"),
+        }
+    }
+}
+
+impl RSAPrivateKey {
+    /// Performs the RSA key-pair validation of NIST SP 800-56B section 6.4
+    /// followed by a pairwise consistency test.
+    ///
+    /// Every check is run even when an earlier one fails so the report is
+    /// complete. Primality is established with Miller-Rabin, so a passing
+    /// report is probabilistic like the rest of the crate.
+    pub fn validate(&self) -> ValidationReport<PrivateKeyCheck> {
+        let mut failures = Vec::new();
+        let zero = BigUint::from(0u64);
+        let one = BigUint::from(1u64);
+
+        let primes = self.primes();
+        let expected_version = if primes.len() > 2 { &one } else { &zero };
+        if &self.version != expected_version {
+            failures.push(PrivateKeyCheck::Version);
+        }
+
+        for (i, prime) in primes.iter().enumerate() {
+            if !is_prime(prime) {
+                failures.push(PrivateKeyCheck::PrimeNotPrime { index: i + 1 });
+            }
+            if primes[..i].contains(prime) {
+                failures.push(PrivateKeyCheck::DuplicatePrime { index: i + 1 });
+            }
+        }
+
+        if primes.iter().copied().product::<BigUint>() != self.modulus {
+            failures.push(PrivateKeyCheck::ModulusMismatch);
+        }
+
+        let e = &self.public_exponent;
+        if e % 2u64 == zero {
+            failures.push(PrivateKeyCheck::PublicExponentEven);
+        }
+        if e <= &(&one << 16) || e >= &(&one << 256) {
+            failures.push(PrivateKeyCheck::PublicExponentOutOfRange);
+        }
+
+        // The totient is meaningless if a prime is 0, so skip what depends on it
+        if primes.iter().all(|prime| *prime > &one) {
+            let carmichael_totient = primes
+                .iter()
+                .map(|prime| *prime - 1u64)
+                .fold(one.clone(), |acc, x| lcm(&acc, &x));
+
+            if e == &zero || gcd(e, &carmichael_totient) != one {
+                failures.push(PrivateKeyCheck::PublicExponentNotCoprime);
+            }
+
+            let d = &self.private_exponent;
+            if d <= &(&one << (self.modulus.bits() / 2)) || d >= &carmichael_totient {
+                failures.push(PrivateKeyCheck::PrivateExponentOutOfRange);
+            }
+            if (d * e) % &carmichael_totient != one {
+                failures.push(PrivateKeyCheck::PrivateExponentNotInverse);
+            }
+
+            let exponents = [&self.exponent1, &self.exponent2]
+                .into_iter()
+                .chain(self.other_prime_infos.iter().map(|info| &info.exponent));
+            for (i, (prime, exponent)) in primes.iter().zip(exponents).enumerate() {
+                if exponent != &(d % (*prime - 1u64)) {
+                    failures.push(PrivateKeyCheck::CrtExponent { index: i + 1 });
+                }
+            }
+
+            // q_inv is reported against p since that is where it is stored,
+            // every other coefficient inverts the product of the earlier primes
+            if (&self.coefficient * &self.prime2) % &self.prime1 != one {
+                failures.push(PrivateKeyCheck::CrtCoefficient { index: 1 });
+            }
+            let mut product = &self.prime1 * &self.prime2;
+            for (i, info) in self.other_prime_infos.iter().enumerate() {
+                if (&info.coefficient * &product) % &info.prime != one {
+                    failures.push(PrivateKeyCheck::CrtCoefficient { index: i + 3 });
+                }
+                product *= &info.prime;
+            }
+        }
+
+        // Pairwise consistency test, only meaningful once the arithmetic is sound
+        if failures.is_empty() {
+            let public_key = self.into();
+            let message = BigInt::from(
+                thread_rng().gen_biguint_range(&BigUint::from(2u64), &(&self.modulus - 1u64)),
+            );
+
+            let ciphertext = rsaep(&public_key, message.clone());
+            if rsadp(self, ciphertext) != Ok(message.clone()) {
+                failures.push(PrivateKeyCheck::PairwiseEncryption);
+            }
+
+            let signature = rsasp1(self, message.clone());
+            if signature.map(|signature| rsavp1(&public_key, signature)) != Ok(message) {
+                failures.push(PrivateKeyCheck::PairwiseSignature);
+            }
+        }
+
+        ValidationReport { failures }
+    }
+}
+
+/// Limits applied by `RSAPublicKey::validate_with_policy`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyPolicy {
+    pub min_modulus_bits: u64,
+    pub max_modulus_bits: u64,
+    /// Trial division is performed with every prime below this bound.
+    pub small_factor_bound: u64,
+}
+
+impl Default for PublicKeyPolicy {
+    /// 2048 to 16384 bit moduli, checked for factors below 752 as suggested
+    /// by SP 800-89 section 5.3.3.
+    fn default() -> Self {
+        Self {
+            min_modulus_bits: 2048,
+            max_modulus_bits: 16384,
+            small_factor_bound: 752,
+        }
+    }
+}
+
+/// A failed check from the SP 800-89 section 5.3.3 partial public key
+/// validation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "check")]
+pub enum PublicKeyCheck {
+    ModulusEven,
+    ModulusPerfectPower,
+    ModulusSmallFactor { factor: u64 },
+    ModulusTooSmall { bits: u64, minimum: u64 },
+    ModulusTooLarge { bits: u64, maximum: u64 },
+    PublicExponentEven,
+    PublicExponentOutOfRange,
+}
+
+impl fmt::Display for PublicKeyCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ModulusEven => write!(f, "modulus is even"),
+            Self::ModulusPerfectPower => write!(f, "modulus is a perfect power"),
+            Self::ModulusSmallFactor { factor } => {
+                write!(f, "modulus is divisible by {}", factor)
+            }
+            Self::ModulusTooSmall { bits, minimum } => {
+                write!(f, "modulus is {} bits, policy minimum is {}", bits, minimum)
+            }
+            Self::ModulusTooLarge { bits, maximum } => {
+                write!(f, "modulus is {} bits, policy maximum is {}", bits, maximum)
+            }
+            Self::PublicExponentEven => write!(f, "public exponent is even"),
+            Self::PublicExponentOutOfRange => {
+                write!(f, "public exponent is outside of [2^16 + 1, 2^256)")
+            }
+        }
+    }
+}
+
+impl RSAPublicKey {
+    /// Performs the SP 800-89 partial public key validation with the default
+    /// `PublicKeyPolicy`.
+    pub fn validate(&self) -> ValidationReport<PublicKeyCheck> {
+        self.validate_with_policy(&PublicKeyPolicy::default())
+    }
+
+    /// Performs the SP 800-89 partial public key validation.
+    ///
+    /// Without the private key the modulus can only be screened for obvious
+    /// weaknesses, so a passing report does not prove that it is a product
+    /// of two large primes.
+    pub fn validate_with_policy(
+        &self,
+        policy: &PublicKeyPolicy,
+    ) -> ValidationReport<PublicKeyCheck> {
+        let mut failures = Vec::new();
+        let zero = BigUint::from(0u64);
+        let one = BigUint::from(1u64);
+        let n = &self.modulus;
+
+        let bits = n.bits();
+        if bits < policy.min_modulus_bits {
+            failures.push(PublicKeyCheck::ModulusTooSmall {
+                bits,
+                minimum: policy.min_modulus_bits,
+            });
+        }
+        if bits > policy.max_modulus_bits {
+            failures.push(PublicKeyCheck::ModulusTooLarge {
+                bits,
+                maximum: policy.max_modulus_bits,
+            });
+        }
+
+        if n % 2u64 == zero {
+            failures.push(PublicKeyCheck::ModulusEven);
+        }
+
+        if is_perfect_power(n).is_some() {
+            failures.push(PublicKeyCheck::ModulusPerfectPower);
+        }
+
+        // 2 is already covered by the parity check
+        for factor in small_primes(policy.small_factor_bound).into_iter().skip(1) {
+            if n % factor == zero && n != &BigUint::from(factor) {
+                failures.push(PublicKeyCheck::ModulusSmallFactor { factor });
+            }
+        }
+
+        let e = &self.public_exponent;
+        if e % 2u64 == zero {
+            failures.push(PublicKeyCheck::PublicExponentEven);
+        }
+        if e <= &(&one << 16) || e >= &(&one << 256) {
+            failures.push(PublicKeyCheck::PublicExponentOutOfRange);
+        }
+
+        ValidationReport { failures }
+    }
+}
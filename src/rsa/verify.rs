@@ -0,0 +1,42 @@
+use asn1_rs::BigInt;
+
+use crate::{
+    asn1::RSAPublicKey,
+    rsa::{
+        primitives::{i2osp, os2ip, rsavp1},
+        sign::emsa_pkcs1_v1_5_encode,
+    },
+};
+
+pub type RSAVerificationResult = Result<(), String>;
+
+/// RSASSA-PKCS1-v1_5 signature verification from RFC 8017 section 8.2.2,
+/// using SHA-256 as the hash function.
+///
+/// The expected encoding is rebuilt from the message and compared as a
+/// whole instead of parsing the recovered one, as the RFC recommends.
+pub fn rsassa_pkcs1_v1_5_verify(
+    signer: &RSAPublicKey,
+    message: &[u8],
+    signature: &[u8],
+) -> RSAVerificationResult {
+    let k = signer.modulus.to_bytes_be().len();
+    if signature.len() != k {
+        return Err("invalid signature".into());
+    }
+
+    let integer_signature = os2ip(signature);
+    if integer_signature >= BigInt::from(signer.modulus.clone()) {
+        return Err("invalid signature".into());
+    }
+
+    let integer_message = rsavp1(signer, integer_signature);
+    let em = i2osp(integer_message, k).map_err(|_| "invalid signature")?;
+    let expected = emsa_pkcs1_v1_5_encode(message, k).map_err(|_| "invalid signature")?;
+
+    if em != expected {
+        return Err("invalid signature".into());
+    }
+
+    Ok(())
+}
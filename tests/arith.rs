@@ -0,0 +1,79 @@
+use naive_rsa::arith::{from_limbs, modpow_ct, to_limbs, MontgomeryContext};
+use num_bigint::BigUint;
+use proptest::prelude::*;
+
+#[test]
+fn rejects_even_and_trivial_moduli() {
+    assert!(MontgomeryContext::new(&BigUint::from(0u64)).is_none());
+    assert!(MontgomeryContext::new(&BigUint::from(1u64)).is_none());
+    assert!(MontgomeryContext::new(&BigUint::from(1u64 << 40)).is_none());
+    assert!(MontgomeryContext::new(&BigUint::from(3u64)).is_some());
+}
+
+#[test]
+fn limbs_round_trip() {
+    let value = (BigUint::from(1u64) << 130) + 12345u64;
+    let limbs = to_limbs(&value, 4);
+
+    assert_eq!(limbs, vec![12345, 0, 4, 0]);
+    assert_eq!(from_limbs(&limbs), value);
+}
+
+#[test]
+fn edge_exponents() {
+    let modulus = BigUint::from(1_000_003u64);
+    let base = BigUint::from(7u64);
+
+    assert_eq!(
+        modpow_ct(&base, &BigUint::from(0u64), &modulus),
+        Some(BigUint::from(1u64))
+    );
+    assert_eq!(
+        modpow_ct(&base, &BigUint::from(1u64), &modulus),
+        Some(base.clone())
+    );
+    assert_eq!(
+        modpow_ct(&BigUint::from(0u64), &BigUint::from(5u64), &modulus),
+        Some(BigUint::from(0u64))
+    );
+    // Unreduced bases and exponents wider than the modulus
+    let exponent = BigUint::from(u128::MAX);
+    assert_eq!(
+        modpow_ct(&(&modulus * 3u64 + 2u64), &exponent, &modulus),
+        Some(BigUint::from(2u64).modpow(&exponent, &modulus))
+    );
+}
+
+proptest! {
+    #[test]
+    fn matches_num_bigint_modpow(
+        base in prop::collection::vec(any::<u64>(), 1..10),
+        exponent in prop::collection::vec(any::<u64>(), 1..10),
+        modulus in prop::collection::vec(any::<u64>(), 1..10),
+    ) {
+        let base = from_limbs(&base);
+        let exponent = from_limbs(&exponent);
+        let modulus = from_limbs(&modulus) | BigUint::from(1u64);
+        prop_assume!(modulus > BigUint::from(1u64));
+
+        prop_assert_eq!(
+            modpow_ct(&base, &exponent, &modulus),
+            Some(base.modpow(&exponent, &modulus))
+        );
+    }
+
+    #[test]
+    fn montgomery_mul_matches_modular_product(
+        a in prop::collection::vec(any::<u64>(), 1..6),
+        b in prop::collection::vec(any::<u64>(), 1..6),
+        modulus in prop::collection::vec(any::<u64>(), 1..6),
+    ) {
+        let modulus = from_limbs(&modulus) | BigUint::from(1u64);
+        prop_assume!(modulus > BigUint::from(1u64));
+        let (a, b) = (from_limbs(&a), from_limbs(&b));
+        let context = MontgomeryContext::new(&modulus).unwrap();
+
+        let product = context.mul(&context.to_montgomery(&a), &context.to_montgomery(&b));
+        prop_assert_eq!(context.from_montgomery(&product), (a * b) % modulus);
+    }
+}
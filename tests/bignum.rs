@@ -0,0 +1,76 @@
+use std::cmp::Ordering;
+
+use naive_rsa::{arith::FixedUint, bignum::BigNum};
+use num_bigint::BigUint;
+use proptest::prelude::*;
+
+fn uint() -> impl Strategy<Value = BigUint> {
+    prop::collection::vec(any::<u32>(), 0..8).prop_map(BigUint::new)
+}
+
+fn nonzero() -> impl Strategy<Value = BigUint> {
+    uint().prop_map(|x| x + 1u64)
+}
+
+fn fixed(x: &BigUint) -> FixedUint {
+    BigNum::from_biguint(x)
+}
+
+#[test]
+fn fixed_width_does_not_follow_the_value() {
+    let small = FixedUint::with_width(&BigUint::from(1u64), 4);
+    let product = small.mul(&small);
+
+    assert_eq!(product.width(), 8);
+    assert_eq!(product.to_biguint(), BigUint::from(1u64));
+    assert_eq!(BigNum::bits(&small), 1);
+    assert_eq!(
+        BigNum::trailing_zeros(&FixedUint::with_width(&BigUint::from(0u64), 3)),
+        None
+    );
+}
+
+proptest! {
+    #[test]
+    fn predicates_match_num_bigint(a in uint(), b in uint()) {
+        prop_assert_eq!(BigNum::is_zero(&fixed(&a)), BigNum::is_zero(&a));
+        prop_assert_eq!(BigNum::is_odd(&fixed(&a)), BigNum::is_odd(&a));
+        prop_assert_eq!(BigNum::bits(&fixed(&a)), a.bits());
+        prop_assert_eq!(BigNum::trailing_zeros(&fixed(&a)), a.trailing_zeros());
+        prop_assert_eq!(BigNum::compare(&fixed(&a), &fixed(&b)), a.cmp(&b));
+    }
+
+    #[test]
+    fn arithmetic_matches_num_bigint(a in uint(), b in uint(), shift in 0..200usize) {
+        let (x, y) = (fixed(&a), fixed(&b));
+
+        prop_assert_eq!(BigNum::add(&x, &y).to_biguint(), &a + &b);
+        prop_assert_eq!(BigNum::mul(&x, &y).to_biguint(), &a * &b);
+        prop_assert_eq!(BigNum::shl(&x, shift).to_biguint(), &a << shift);
+        prop_assert_eq!(BigNum::shr(&x, shift).to_biguint(), &a >> shift);
+
+        let (larger, smaller) = if a.cmp(&b) == Ordering::Less { (&b, &a) } else { (&a, &b) };
+        prop_assert_eq!(BigNum::sub(&fixed(larger), &fixed(smaller)).to_biguint(), larger - smaller);
+    }
+
+    #[test]
+    fn division_matches_num_bigint(a in uint(), b in nonzero()) {
+        let (quotient, remainder) = BigNum::div_rem(&fixed(&a), &fixed(&b));
+
+        prop_assert_eq!(quotient.to_biguint(), &a / &b);
+        prop_assert_eq!(remainder.to_biguint(), &a % &b);
+    }
+
+    #[test]
+    fn modular_arithmetic_matches_num_bigint(a in uint(), b in uint(), e in uint(), m in nonzero()) {
+        let (x, y, m_fixed) = (fixed(&a), fixed(&b), fixed(&m));
+
+        prop_assert_eq!(BigNum::mod_mul(&x, &y, &m_fixed).to_biguint(), (&a * &b) % &m);
+        prop_assert_eq!(
+            BigNum::mod_sub(&x, &y, &m_fixed).to_biguint(),
+            BigNum::mod_sub(&a, &b, &m)
+        );
+        // Covers both the Montgomery path for odd moduli and the ladder
+        prop_assert_eq!(BigNum::mod_pow(&x, &fixed(&e), &m_fixed).to_biguint(), a.modpow(&e, &m));
+    }
+}
@@ -0,0 +1,130 @@
+use std::io::Read;
+
+use naive_rsa::{
+    asn1::RSAPublicKey,
+    container::{
+        armor::{armor, dearmor, is_armored, ArmorReader},
+        decrypt, encrypt, fingerprint, is_container, Header, Scheme,
+    },
+    rsa::gen_keypair::generate_keypair,
+};
+
+#[test]
+fn round_trips_every_scheme() {
+    let key = generate_keypair(1024);
+    let public_key = RSAPublicKey::from(&key);
+
+    for scheme in [Scheme::Pkcs1V15, Scheme::Oaep, Scheme::Hybrid] {
+        let container = encrypt(&public_key, scheme, b"attack at dawn").unwrap();
+        assert!(is_container(&container));
+
+        let (header, plaintext) = decrypt(&key, &container).unwrap();
+        assert_eq!(header.scheme, scheme);
+        assert_eq!(header.recipients, [fingerprint(&public_key)]);
+        assert_eq!(plaintext, b"attack at dawn");
+    }
+}
+
+#[test]
+fn header_layout_is_stable() {
+    let public_key = RSAPublicKey::from(&generate_keypair(512));
+    let header = Header::new(Scheme::Oaep, std::slice::from_ref(&public_key), vec![1]);
+
+    let bytes = header.to_bytes();
+    assert_eq!(&bytes[..12], b"NAIVERSA\x02\x02\x00\x01");
+    assert_eq!(&bytes[12..44], &fingerprint(&public_key));
+    assert_eq!(&bytes[44..], &[0x00, 0x01, 0x01]);
+
+    assert_eq!(Header::read(&mut &bytes[..]), Ok(header));
+}
+
+#[test]
+fn reads_version_1_headers() {
+    let public_key = RSAPublicKey::from(&generate_keypair(512));
+    let mut bytes = b"NAIVERSA\x01\x02".to_vec();
+    bytes.extend_from_slice(&fingerprint(&public_key));
+    bytes.extend_from_slice(&[0x00, 0x01, 0x01]);
+
+    let header = Header::read(&mut &bytes[..]).unwrap();
+    assert_eq!(header.version, 1);
+    assert_eq!(header.recipients, [fingerprint(&public_key)]);
+    // Written back unchanged, since sealed payloads authenticate it
+    assert_eq!(header.to_bytes(), bytes);
+}
+
+#[test]
+fn detects_the_wrong_key_up_front() {
+    let key = generate_keypair(1024);
+    let other_key = generate_keypair(1024);
+    let container = encrypt(&(&key).into(), Scheme::Oaep, b"for someone else").unwrap();
+
+    let error = decrypt(&other_key, &container).unwrap_err();
+    let expected = fingerprint(&(&key).into());
+    assert!(error.contains(&format!("{:02x}:{:02x}", expected[0], expected[1])));
+}
+
+#[test]
+fn rejects_malformed_headers() {
+    let key = generate_keypair(1024);
+    let container = encrypt(&(&key).into(), Scheme::Pkcs1V15, b"message").unwrap();
+
+    let mut wrong_version = container.clone();
+    wrong_version[8] = 3;
+    assert_eq!(
+        decrypt(&key, &wrong_version).unwrap_err(),
+        "unsupported container version 3"
+    );
+
+    let mut no_recipients = container.clone();
+    no_recipients[10..12].copy_from_slice(&[0x00, 0x00]);
+    assert_eq!(
+        decrypt(&key, &no_recipients).unwrap_err(),
+        "the container lists no recipients"
+    );
+
+    let mut wrong_scheme = container.clone();
+    wrong_scheme[9] = 0xff;
+    assert!(decrypt(&key, &wrong_scheme).is_err());
+
+    assert!(decrypt(&key, &container[..20]).is_err());
+    assert!(decrypt(&key, b"not a container at all, not at all").is_err());
+}
+
+#[test]
+fn armor_round_trips() {
+    let key = generate_keypair(1024);
+    let container = encrypt(&(&key).into(), Scheme::Oaep, b"armored").unwrap();
+
+    let armored = armor(&container);
+    assert!(is_armored(armored.as_bytes()));
+    assert!(armored.lines().all(|line| line.len() <= 64));
+    assert_eq!(dearmor(&armored).unwrap(), container);
+}
+
+#[test]
+fn armor_reader_accepts_other_line_lengths() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    let body = base64::encode(&bytes);
+
+    // Lines of 76 and then 10 characters, as other base64 tools wrap them
+    let mut text = String::from("\n-----BEGIN NAIVE-RSA MESSAGE-----\n");
+    for line in body.as_bytes().chunks(76) {
+        for part in line.chunks(66) {
+            text += std::str::from_utf8(part).unwrap();
+            text += "\r\n";
+        }
+    }
+    text += "-----END NAIVE-RSA MESSAGE-----\n";
+
+    let mut decoded = Vec::new();
+    ArmorReader::new(text.as_bytes())
+        .unwrap()
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, bytes);
+
+    // A missing footer is an error rather than a silent truncation
+    let truncated = &text[..text.find("-----END").unwrap()];
+    let mut reader = ArmorReader::new(truncated.as_bytes()).unwrap();
+    assert!(reader.read_to_end(&mut Vec::new()).is_err());
+}
@@ -0,0 +1,95 @@
+use naive_rsa::{
+    asn1::{RSAPrivateKey, RSAPublicKey},
+    rsa::{
+        decrypt::rsaes_pkcs1_v1_5_decrypt,
+        encrypt::rsaes_pkcs1_v1_5_encrypt,
+        fault::{FaultDetected, PrivateKeyError},
+        gen_keypair::{generate_keypair, generate_multiprime_keypair},
+        hazmat::{rsadp, rsasp1, rsavp1},
+        sign::rsassa_pkcs1_v1_5_sign,
+    },
+};
+use num_bigint::BigInt;
+
+// A persistent error in one CRT exponent behaves exactly like a computation
+// fault in that half: the result is still right modulo the other prime.
+fn fault_first_half(key: &mut RSAPrivateKey) {
+    key.exponent1 += 1u64;
+}
+
+fn fault_second_half(key: &mut RSAPrivateKey) {
+    key.exponent2 += 2u64;
+}
+
+#[test]
+fn healthy_signature_is_released() {
+    let key = generate_keypair(512);
+    let public_key = RSAPublicKey::from(&key);
+    let message = BigInt::from(0x5eed_u64);
+
+    let signature = rsasp1(&key, &message).unwrap();
+    assert_eq!(rsavp1(&public_key, &signature).unwrap(), message);
+}
+
+#[test]
+fn faulty_crt_signature_is_withheld() {
+    for fault in [fault_first_half, fault_second_half] {
+        let mut key = generate_keypair(512);
+        fault(&mut key);
+
+        assert_eq!(
+            rsasp1(&key, &BigInt::from(0x5eed_u64)),
+            Err(PrivateKeyError::Fault)
+        );
+    }
+}
+
+#[test]
+fn faulty_multiprime_signature_is_withheld() {
+    let mut key = generate_multiprime_keypair(768, 3).unwrap();
+    key.other_prime_infos[0].exponent += 1u64;
+
+    assert_eq!(
+        rsasp1(&key, &BigInt::from(0x5eed_u64)),
+        Err(PrivateKeyError::Fault)
+    );
+}
+
+#[test]
+fn faulty_decryption_is_withheld() {
+    let mut key = generate_keypair(512);
+    let ciphertext = rsaes_pkcs1_v1_5_encrypt(&RSAPublicKey::from(&key), b"secret").unwrap();
+    fault_first_half(&mut key);
+
+    assert_eq!(
+        rsaes_pkcs1_v1_5_decrypt(&key, &ciphertext),
+        Err(PrivateKeyError::Fault)
+    );
+    assert_eq!(
+        rsadp(&key, &BigInt::from(12345u64)),
+        Err(PrivateKeyError::Fault)
+    );
+}
+
+#[test]
+fn faults_are_told_apart_from_malformed_input() {
+    let mut key = generate_keypair(512);
+    fault_second_half(&mut key);
+
+    assert_eq!(
+        rsassa_pkcs1_v1_5_sign(&key, b"message"),
+        Err(PrivateKeyError::Fault)
+    );
+    assert!(matches!(
+        rsadp(&key, &BigInt::from(-1i64)),
+        Err(PrivateKeyError::Malformed(_))
+    ));
+    assert!(matches!(
+        rsaes_pkcs1_v1_5_decrypt(&key, &[0x00; 3]),
+        Err(PrivateKeyError::Malformed(_))
+    ));
+    assert_eq!(
+        PrivateKeyError::Fault.to_string(),
+        FaultDetected.to_string()
+    );
+}
@@ -0,0 +1,160 @@
+use naive_rsa::hash::{mgf1, Digest, HashAlgorithm, Md5, Sha1, Sha224, Sha256, Sha384, Sha512};
+
+// The messages of the FIPS 180 examples. The 56 and 112 byte messages leave
+// no room for the length in the last block, so their padding takes a block
+// of its own.
+const ABC: &[u8] = b"abc";
+const TWO_BLOCKS: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+const LONG: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Checks `D` against the expected hashes of the empty message, `ABC`,
+/// `TWO_BLOCKS`, `LONG` and a million `a`s, in that order.
+fn check<D: Digest>(expected: [&str; 5]) {
+    let million_a = vec![b'a'; 1_000_000];
+    let messages: [&[u8]; 5] = [b"", ABC, TWO_BLOCKS, LONG, &million_a];
+
+    for (message, expected) in messages.iter().zip(expected) {
+        let hash = D::digest(message);
+        assert_eq!(hash.len(), D::OUTPUT_SIZE);
+        assert_eq!(hash, decode_hex(expected), "{} bytes", message.len());
+    }
+}
+
+#[test]
+fn sha1_matches_nist_vectors() {
+    check::<Sha1>([
+        "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+        "a9993e364706816aba3e25717850c26c9cd0d89d",
+        "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+        "a49b2446a02c645bf419f995b67091253a04a259",
+        "34aa973cd4c4daa4f61eeb2bdbad27316534016f",
+    ]);
+}
+
+#[test]
+fn sha224_matches_nist_vectors() {
+    check::<Sha224>([
+        "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+        "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+        "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+        "c97ca9a559850ce97a04a96def6d99a9e0e0e2ab14e6b8df265fc0b3",
+        "20794655980c91d8bbb4c1ea97618a4bf03f42581948b2ee4ee7ad67",
+    ]);
+}
+
+#[test]
+fn sha256_matches_nist_vectors() {
+    check::<Sha256>([
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+    ]);
+}
+
+#[test]
+fn sha384_matches_nist_vectors() {
+    check::<Sha384>([
+        "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b",
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+        "3391fdddfc8dc7393707a65b1b4709397cf8b1d162af05abfe8f450de5f36bc6b0455a8520bc4e6f5fe95b1fe3c8452b",
+        "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039",
+        "9d0e1809716474cb086e834e310a4a1ced149e9c00f248527972cec5704c2a5b07b8b3dc38ecc4ebae97ddd87f3d8985",
+    ]);
+}
+
+#[test]
+fn sha512_matches_nist_vectors() {
+    check::<Sha512>([
+        "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c33596fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445",
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+        "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973ebde0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b",
+    ]);
+}
+
+#[test]
+fn md5_matches_rfc_1321_vectors() {
+    for (message, expected) in [
+        (&b""[..], "d41d8cd98f00b204e9800998ecf8427e"),
+        (b"a", "0cc175b9c0f1b6a831c399e269772661"),
+        (b"abc", "900150983cd24fb0d6963f7d28e17f72"),
+        (b"message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+        (
+            b"abcdefghijklmnopqrstuvwxyz",
+            "c3fcd3d76192e4007dfb496cca67e13b",
+        ),
+        (
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            "d174ab98d277d9f5a5611c2c9f419d9f",
+        ),
+        (
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "57edf4a22be3c955ac49da2e2107b67a",
+        ),
+    ] {
+        assert_eq!(Md5::digest(message), decode_hex(expected));
+    }
+}
+
+#[test]
+fn updates_in_pieces_match_one_call() {
+    fn check<D: Digest>() {
+        let message: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let expected = D::digest(&message);
+
+        for split in 0..=message.len() {
+            let mut hasher = D::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..]);
+            assert_eq!(hasher.finalize(), expected, "split at {}", split);
+        }
+
+        let mut hasher = D::new();
+        for byte in &message {
+            hasher.update(std::slice::from_ref(byte));
+        }
+        assert_eq!(hasher.finalize(), expected);
+    }
+
+    check::<Md5>();
+    check::<Sha1>();
+    check::<Sha256>();
+    check::<Sha512>();
+}
+
+#[test]
+fn algorithms_match_their_types() {
+    for (algorithm, hash, block_size) in [
+        (HashAlgorithm::Sha1, Sha1::digest(LONG), 64),
+        (HashAlgorithm::Sha224, Sha224::digest(LONG), 64),
+        (HashAlgorithm::Sha256, Sha256::digest(LONG), 64),
+        (HashAlgorithm::Sha384, Sha384::digest(LONG), 128),
+        (HashAlgorithm::Sha512, Sha512::digest(LONG), 128),
+    ] {
+        assert_eq!(algorithm.digest(LONG), hash);
+        assert_eq!(algorithm.digest_parts(&[&LONG[..7], &LONG[7..]]), hash);
+        assert_eq!(algorithm.output_size(), hash.len());
+        assert_eq!(algorithm.block_size(), block_size);
+    }
+}
+
+#[test]
+fn mgf1_matches_known_masks() {
+    assert_eq!(mgf1::<Sha1>(b"foo", 3), decode_hex("1ac907"));
+    assert_eq!(mgf1::<Sha1>(b"bar", 5), decode_hex("bc0c655e01"));
+    assert_eq!(
+        mgf1::<Sha256>(b"bar", 50),
+        decode_hex("382576a7841021cc28fc4c0948753fb8312090cea942ea4c4e735d10dc724b155f9f6069f289d61daca0cb814502ef04eae1")
+    );
+    assert!(mgf1::<Sha256>(b"bar", 0).is_empty());
+}
@@ -0,0 +1,217 @@
+use naive_rsa::{
+    hash::{Digest, Sha1, Sha224, Sha256, Sha384, Sha512},
+    kdf::{hkdf, hkdf_expand, hkdf_extract, hmac, pbkdf2, Hmac},
+};
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Checks HMAC-`D` against test cases 1, 2 and 6 of RFC 4231, or of RFC 2202
+/// for SHA-1 whose case 6 key is 80 rather than 131 bytes. The last one has
+/// a key longer than the block, which is hashed first.
+fn check_hmac<D: Digest>(long_key_length: usize, expected: [&str; 3]) {
+    let long_key = vec![0xaa; long_key_length];
+    let cases: [(&[u8], &[u8]); 3] = [
+        (&[0x0b; 20], b"Hi There"),
+        (b"Jefe", b"what do ya want for nothing?"),
+        (
+            &long_key,
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        ),
+    ];
+
+    for ((key, message), expected) in cases.iter().zip(expected) {
+        assert_eq!(hmac::<D>(key, message), decode_hex(expected));
+    }
+}
+
+#[test]
+fn hmac_matches_rfc_vectors() {
+    check_hmac::<Sha1>(
+        80,
+        [
+            "b617318655057264e28bc0b6fb378c8ef146be00",
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+            "aa4ae5e15272d00e95705637ce8a3b55ed402112",
+        ],
+    );
+    check_hmac::<Sha224>(
+        131,
+        [
+            "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+            "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+            "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+        ],
+    );
+    check_hmac::<Sha256>(
+        131,
+        [
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        ],
+    );
+    check_hmac::<Sha384>(
+        131,
+        [
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+            "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+        ],
+    );
+    check_hmac::<Sha512>(
+        131,
+        [
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+        ],
+    );
+}
+
+#[test]
+fn hmac_can_be_fed_incrementally() {
+    let mut mac = Hmac::<Sha256>::new(b"Jefe");
+    mac.update(b"what do ya want ");
+    let copy = mac.clone();
+    mac.update(b"for nothing?");
+
+    assert_eq!(
+        mac.finalize(),
+        hmac::<Sha256>(b"Jefe", b"what do ya want for nothing?")
+    );
+    assert_eq!(
+        copy.finalize(),
+        hmac::<Sha256>(b"Jefe", b"what do ya want ")
+    );
+}
+
+#[test]
+fn hkdf_matches_rfc_5869_vectors() {
+    // Test case 1, basic with SHA-256
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let pseudorandom_key = hkdf_extract::<Sha256>(&salt, &[0x0b; 22]);
+    assert_eq!(
+        pseudorandom_key,
+        decode_hex("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+    );
+    assert_eq!(
+        hkdf_expand::<Sha256>(&pseudorandom_key, &info, 42).unwrap(),
+        decode_hex(
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        )
+    );
+
+    // Test case 2, longer inputs and outputs
+    let secret: Vec<u8> = (0x00..=0x4f).collect();
+    let salt: Vec<u8> = (0x60..=0xaf).collect();
+    let info: Vec<u8> = (0xb0..=0xff).collect();
+    assert_eq!(
+        hkdf::<Sha256>(&salt, &secret, &info, 82).unwrap(),
+        decode_hex("b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71cc30c58179ec3e87c14c01d5c1f3434f1d87")
+    );
+
+    // Test case 3, empty salt and info
+    assert_eq!(
+        hkdf::<Sha256>(&[], &[0x0b; 22], &[], 42).unwrap(),
+        decode_hex(
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        )
+    );
+
+    // Test case 4, basic with SHA-1
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    assert_eq!(
+        hkdf::<Sha1>(&salt, &[0x0b; 11], &info, 42).unwrap(),
+        decode_hex(
+            "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2c22e422478d305f3f896"
+        )
+    );
+
+    // Test case 7, SHA-1 with an empty salt, which is the same as zeros
+    assert_eq!(
+        hkdf_extract::<Sha1>(&[], &[0x0c; 22]),
+        hkdf_extract::<Sha1>(&[0x00; 20], &[0x0c; 22])
+    );
+    assert_eq!(
+        hkdf::<Sha1>(&[], &[0x0c; 22], &[], 42).unwrap(),
+        decode_hex(
+            "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5673a081d70cce7acfc48"
+        )
+    );
+}
+
+#[test]
+fn hkdf_limits_the_output_length() {
+    assert_eq!(
+        hkdf::<Sha256>(&[], b"secret", &[], 255 * 32).unwrap().len(),
+        255 * 32
+    );
+    assert!(hkdf::<Sha256>(&[], b"secret", &[], 255 * 32 + 1).is_err());
+}
+
+#[test]
+fn pbkdf2_matches_rfc_6070_vectors() {
+    for (password, salt, iterations, expected) in [
+        (
+            &b"password"[..],
+            &b"salt"[..],
+            1,
+            "0c60c80f961f0e71f3a9b524af6012062fe037a6",
+        ),
+        (
+            b"password",
+            b"salt",
+            2,
+            "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957",
+        ),
+        (
+            b"password",
+            b"salt",
+            4096,
+            "4b007901b765489abead49d926f721d065a429c1",
+        ),
+        (
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            "3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038",
+        ),
+        (
+            b"pass\0word",
+            b"sa\0lt",
+            4096,
+            "56fa6aa75548099dcc37d7f03425e0c3",
+        ),
+    ] {
+        let expected = decode_hex(expected);
+        assert_eq!(
+            pbkdf2::<Sha1>(password, salt, iterations, expected.len()).unwrap(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn pbkdf2_matches_rfc_7914_vectors() {
+    // Section 11, with HMAC-SHA-256 and output longer than one hash
+    assert_eq!(
+        pbkdf2::<Sha256>(b"passwd", b"salt", 1, 64).unwrap(),
+        decode_hex("55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783")
+    );
+    assert_eq!(
+        pbkdf2::<Sha256>(b"Password", b"NaCl", 80000, 64).unwrap(),
+        decode_hex("4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d")
+    );
+}
+
+#[test]
+fn pbkdf2_rejects_zero_iterations() {
+    assert!(pbkdf2::<Sha256>(b"password", b"salt", 0, 32).is_err());
+}
@@ -0,0 +1,248 @@
+use naive_rsa::math::{
+    crt, extended_gcd, gcd, is_perfect_power, jacobi, lcm, legendre, mod_inverse, modulo, nth_root,
+    recover_primes, sqrt_mod,
+};
+use num_bigint::{BigInt, BigUint};
+use proptest::prelude::*;
+
+const PRIMES: [u64; 10] = [3, 5, 7, 13, 17, 97, 7919, 65537, 1000003, 2147483647];
+
+fn int(x: i128) -> BigInt {
+    BigInt::from(x)
+}
+
+fn uint(x: u128) -> BigUint {
+    BigUint::from(x)
+}
+
+#[test]
+fn gcd_with_zero_terminates() {
+    assert_eq!(gcd(&uint(0), &uint(12)), uint(12));
+    assert_eq!(gcd(&uint(12), &uint(0)), uint(12));
+    assert_eq!(gcd(&uint(0), &uint(0)), uint(0));
+}
+
+#[test]
+fn lcm_with_zero_is_zero() {
+    assert_eq!(lcm(&uint(0), &uint(12)), uint(0));
+    assert_eq!(lcm(&uint(12), &uint(0)), uint(0));
+    assert_eq!(lcm(&uint(0), &uint(0)), uint(0));
+}
+
+#[test]
+fn recover_primes_factors_known_keys() {
+    // The textbook key of Wikipedia, whose d is reduced modulo phi(n)
+    let (p, q) = recover_primes(&uint(3233), &uint(17), &uint(2753)).unwrap();
+    assert_eq!(p.clone().min(q.clone()), uint(53));
+    assert_eq!(p.max(q), uint(61));
+
+    // The same primes with d reduced modulo lambda(n) = 780
+    let d = mod_inverse(&int(17), &int(780)).unwrap();
+    let (p, q) = recover_primes(&uint(3233), &uint(17), &d.try_into().unwrap()).unwrap();
+    assert_eq!(&p * &q, uint(3233));
+
+    let (p, q) = (uint(2147483647), uint(1000003));
+    let n = &p * &q;
+    let lambda = lcm(&(&p - 1u32), &(&q - 1u32));
+    let d = mod_inverse(&int(65537), &lambda.into()).unwrap();
+    let (r, s) = recover_primes(&n, &uint(65537), &d.try_into().unwrap()).unwrap();
+    assert_eq!(r.clone().max(s.clone()), p);
+    assert_eq!(r.min(s), q);
+}
+
+#[test]
+fn recover_primes_rejects_mismatched_exponents() {
+    // d * e - 1 is odd, so cannot be a multiple of lambda(n)
+    assert_eq!(recover_primes(&uint(3233), &uint(17), &uint(2754)), None);
+    assert_eq!(recover_primes(&uint(3234), &uint(17), &uint(2753)), None);
+    assert_eq!(recover_primes(&uint(3233), &uint(1), &uint(1)), None);
+}
+
+#[test]
+fn jacobi_known_values() {
+    // Worked by hand with reciprocity and the supplementary laws
+    assert_eq!(jacobi(&int(1001), &int(9907)), Some(-1));
+    assert_eq!(jacobi(&int(19), &int(45)), Some(1));
+    assert_eq!(jacobi(&int(8), &int(21)), Some(-1));
+    assert_eq!(jacobi(&int(5), &int(21)), Some(1));
+    assert_eq!(jacobi(&int(6), &int(21)), Some(0));
+    assert_eq!(jacobi(&int(3), &int(4)), None);
+}
+
+#[test]
+fn crt_rejects_inconsistent_system() {
+    assert_eq!(crt(&[(int(1), int(4)), (int(2), int(6))]), None);
+    assert_eq!(
+        crt(&[(int(1), int(4)), (int(3), int(6))]),
+        Some((int(9), int(12)))
+    );
+}
+
+#[test]
+fn sqrt_mod_non_residue() {
+    assert_eq!(sqrt_mod(&uint(3), &uint(7)), None);
+    assert_eq!(sqrt_mod(&uint(0), &uint(7)), Some(uint(0)));
+}
+
+#[test]
+fn sqrt_mod_composite_modulus_terminates() {
+    // Tonelli-Shanks used to loop forever on these
+    assert_eq!(sqrt_mod(&uint(4), &uint(21)), None);
+    assert_eq!(sqrt_mod(&uint(2), &uint(33)), None);
+    assert_eq!(sqrt_mod(&uint(4), &uint(9)), None);
+
+    // Odd perfect squares have no Jacobi -1 to search for
+    assert_eq!(sqrt_mod(&uint(4), &uint(10007 * 10007)), None);
+    let mersenne_61 = uint((1 << 61) - 1);
+    assert_eq!(sqrt_mod(&uint(4), &(&mersenne_61 * &mersenne_61)), None);
+
+    for p in (9..200u128).step_by(2) {
+        for a in 0..p {
+            if let Some(root) = sqrt_mod(&uint(a), &uint(p)) {
+                assert_eq!(&root * &root % uint(p), uint(a), "{} mod {}", a, p);
+            }
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn extended_gcd_gives_bezout_coefficients(a in any::<i64>(), b in any::<i64>()) {
+        let (a, b) = (int(a.into()), int(b.into()));
+        let (g, x, y) = extended_gcd(&a, &b);
+
+        prop_assert_eq!(&a * x + &b * y, g.clone());
+        prop_assert!(g >= int(0));
+        let expected = gcd(&a.magnitude().clone(), &b.magnitude().clone());
+        prop_assert_eq!(g, BigInt::from(expected));
+    }
+
+    #[test]
+    fn gcd_divides_both(a in any::<u64>(), b in any::<u64>()) {
+        let (a, b) = (uint(a.into()), uint(b.into()));
+        let g = gcd(&a, &b);
+
+        if g != uint(0) {
+            prop_assert_eq!(&a % &g, uint(0));
+            prop_assert_eq!(&b % &g, uint(0));
+        }
+    }
+
+    #[test]
+    fn lcm_is_common_multiple(a in 1..u64::MAX, b in 1..u64::MAX) {
+        let (a, b) = (uint(a.into()), uint(b.into()));
+        let l = lcm(&a, &b);
+
+        prop_assert_eq!(&l % &a, uint(0));
+        prop_assert_eq!(&l % &b, uint(0));
+        prop_assert_eq!(&l * gcd(&a, &b), &a * &b);
+    }
+
+    #[test]
+    fn mod_inverse_inverts_coprime_values(a in any::<i64>(), m in 1..i64::MAX) {
+        let (a, m) = (int(a.into()), int(m.into()));
+
+        match mod_inverse(&a, &m) {
+            Some(inverse) => {
+                prop_assert!(inverse >= int(0) && inverse < m);
+                prop_assert_eq!(modulo(&(&a * inverse), &m), modulo(&int(1), &m));
+            }
+            None => {
+                let (g, _, _) = extended_gcd(&a, &m);
+                prop_assert_ne!(g, int(1));
+            }
+        }
+    }
+
+    #[test]
+    fn crt_satisfies_every_congruence(
+        congruences in prop::collection::vec((any::<u32>(), 1..10_000u32), 1..6)
+    ) {
+        let congruences: Vec<(BigInt, BigInt)> = congruences
+            .into_iter()
+            .map(|(a, m)| (int(a.into()), int(m.into())))
+            .collect();
+
+        if let Some((x, m)) = crt(&congruences) {
+            for (a_i, m_i) in &congruences {
+                prop_assert_eq!(modulo(&x, m_i), modulo(a_i, m_i));
+                prop_assert_eq!(&m % m_i, int(0));
+            }
+        }
+    }
+
+    #[test]
+    fn crt_always_solves_coprime_moduli(a in any::<u64>(), b in any::<u64>(), c in any::<u64>()) {
+        let congruences = [
+            (int(a.into()), int(7919)),
+            (int(b.into()), int(65537)),
+            (int(c.into()), int(1000003)),
+        ];
+
+        let (x, m) = crt(&congruences).unwrap();
+        prop_assert_eq!(m, int(7919 * 65537 * 1000003));
+        for (a_i, m_i) in &congruences {
+            prop_assert_eq!(modulo(&x, m_i), modulo(a_i, m_i));
+        }
+    }
+
+    #[test]
+    fn legendre_matches_euler_criterion(a in any::<u64>(), i in 0..PRIMES.len()) {
+        let p = uint(PRIMES[i].into());
+        let a = uint(a.into());
+        let euler = a.modpow(&((&p - 1u64) / 2u64), &p);
+
+        let expected = if euler == uint(0) {
+            0
+        } else if euler == uint(1) {
+            1
+        } else {
+            -1
+        };
+        prop_assert_eq!(legendre(&a.into(), &p.into()), Some(expected));
+    }
+
+    #[test]
+    fn jacobi_is_multiplicative_in_the_modulus(a in any::<i64>(), i in 0..PRIMES.len(), j in 0..PRIMES.len()) {
+        let a = int(a.into());
+        let (p, q) = (int(PRIMES[i].into()), int(PRIMES[j].into()));
+
+        let product = jacobi(&a, &p).unwrap() * jacobi(&a, &q).unwrap();
+        prop_assert_eq!(jacobi(&a, &(&p * &q)), Some(product));
+    }
+
+    #[test]
+    fn nth_root_is_floor(n in any::<u128>(), k in 1..12u32) {
+        let n = uint(n);
+        let root = nth_root(&n, k);
+
+        prop_assert!(root.pow(k) <= n);
+        prop_assert!((root + 1u64).pow(k) > n);
+    }
+
+    #[test]
+    fn perfect_powers_are_detected(base in 2..100_000u64, k in 2..8u32) {
+        let n = uint(base.into()).pow(k);
+        let (b, e) = is_perfect_power(&n).unwrap();
+
+        prop_assert_eq!(b.pow(e), n);
+        prop_assert!(e >= k);
+    }
+
+    #[test]
+    fn prime_products_are_not_perfect_powers(i in 0..PRIMES.len(), j in 0..PRIMES.len()) {
+        prop_assume!(i != j);
+        let n = uint(PRIMES[i].into()) * uint(PRIMES[j].into());
+
+        prop_assert_eq!(is_perfect_power(&n), None);
+    }
+
+    #[test]
+    fn sqrt_mod_squares_back(x in any::<u64>(), i in 0..PRIMES.len()) {
+        let p = uint(PRIMES[i].into());
+        let a = (uint(x.into()) * uint(x.into())) % &p;
+        let root = sqrt_mod(&a, &p).unwrap();
+
+        prop_assert_eq!((&root * &root) % &p, a);
+    }
+}
@@ -0,0 +1,144 @@
+use naive_rsa::{
+    asn1::RSAPublicKey,
+    rsa::{
+        fault::PrivateKeyError,
+        gen_keypair::generate_keypair,
+        hazmat::{i2osp, os2ip, rsadp, rsadp_octets, rsaep, rsaep_octets, rsasp1, rsavp1},
+    },
+};
+use num_bigint::BigInt;
+use proptest::prelude::*;
+
+// Cases follow the definitions in RFC 8017 section 4: I2OSP writes
+// x = x_(xLen-1) 256^(xLen-1) + ... + x_0 as the octets X_1 ... X_xLen with
+// X_i = x_(xLen-i), and OS2IP is its inverse.
+
+#[test]
+fn i2osp_rfc_8017_cases() {
+    assert_eq!(i2osp(BigInt::from(0u64), 0), Ok(vec![]));
+    assert_eq!(i2osp(BigInt::from(0u64), 3), Ok(vec![0x00, 0x00, 0x00]));
+    assert_eq!(i2osp(BigInt::from(1u64), 1), Ok(vec![0x01]));
+    assert_eq!(i2osp(BigInt::from(255u64), 1), Ok(vec![0xff]));
+    assert_eq!(i2osp(BigInt::from(256u64), 2), Ok(vec![0x01, 0x00]));
+    assert_eq!(
+        i2osp(BigInt::from(0x0102_0304u64), 6),
+        Ok(vec![0x00, 0x00, 0x01, 0x02, 0x03, 0x04])
+    );
+}
+
+#[test]
+fn i2osp_integer_too_large() {
+    assert_eq!(
+        i2osp(BigInt::from(1u64), 0),
+        Err("integer too large".to_string())
+    );
+    assert_eq!(
+        i2osp(BigInt::from(256u64), 1),
+        Err("integer too large".to_string())
+    );
+
+    // 256^xLen - 1 is the largest value that fits
+    let limit: BigInt = BigInt::from(1u64) << (8 * 32);
+    assert_eq!(i2osp(&limit - 1u64, 32), Ok(vec![0xff; 32]));
+    assert_eq!(i2osp(limit, 32), Err("integer too large".to_string()));
+}
+
+#[test]
+fn i2osp_rejects_negative_integers() {
+    assert!(i2osp(BigInt::from(-1i64), 4).is_err());
+}
+
+#[test]
+fn os2ip_rfc_8017_cases() {
+    assert_eq!(os2ip(&[]), BigInt::from(0u64));
+    assert_eq!(os2ip(&[0x00, 0x00]), BigInt::from(0u64));
+    assert_eq!(os2ip(&[0x01, 0x00]), BigInt::from(256u64));
+    assert_eq!(os2ip(&[0xff]), BigInt::from(255u64));
+    assert_eq!(
+        os2ip(&[0x00, 0x00, 0x01, 0x02, 0x03, 0x04]),
+        BigInt::from(0x0102_0304u64)
+    );
+}
+
+proptest! {
+    #[test]
+    fn i2osp_inverts_os2ip(octets in prop::collection::vec(any::<u8>(), 0..300)) {
+        let integer = os2ip(&octets);
+        prop_assert_eq!(i2osp(integer, octets.len()), Ok(octets));
+    }
+
+    #[test]
+    fn os2ip_inverts_i2osp(x in any::<u128>(), padding in 0..8usize) {
+        let integer = BigInt::from(x);
+        let octets = i2osp(integer.clone(), 16 + padding).unwrap();
+
+        prop_assert_eq!(octets.len(), 16 + padding);
+        prop_assert_eq!(os2ip(&octets), integer);
+    }
+}
+
+#[test]
+fn hazmat_primitives_round_trip() {
+    let key = generate_keypair(512);
+    let public_key = (&key).into();
+    let message = BigInt::from(42u64);
+
+    let ciphertext = rsaep(&public_key, &message).unwrap();
+    assert_eq!(rsadp(&key, &ciphertext).unwrap(), message);
+
+    let signature = rsasp1(&key, &message).unwrap();
+    assert_eq!(rsavp1(&public_key, &signature).unwrap(), message);
+
+    let ciphertext = rsaep_octets(&public_key, b"textbook").unwrap();
+    assert_eq!(ciphertext.len(), 64);
+    let message = rsadp_octets(&key, &ciphertext).unwrap();
+    assert_eq!(&message[56..], b"textbook");
+    assert!(message[..56].iter().all(|&byte| byte == 0x00));
+}
+
+#[test]
+fn hazmat_primitives_check_range() {
+    let key = generate_keypair(512);
+    let public_key = (&key).into();
+    let modulus = BigInt::from(key.modulus.clone());
+
+    assert_eq!(
+        rsaep(&public_key, &modulus),
+        Err(PrivateKeyError::Malformed(
+            "message representative out of range".into()
+        ))
+    );
+    assert_eq!(
+        rsadp(&key, &BigInt::from(-1i64)),
+        Err(PrivateKeyError::Malformed(
+            "ciphertext representative out of range".into()
+        ))
+    );
+    assert_eq!(
+        rsasp1(&key, &(&modulus + 1u64)),
+        Err(PrivateKeyError::Malformed(
+            "message representative out of range".into()
+        ))
+    );
+    assert_eq!(
+        rsavp1(&public_key, &modulus),
+        Err(PrivateKeyError::Malformed(
+            "signature representative out of range".into()
+        ))
+    );
+    assert!(rsaep(&public_key, &(&modulus - 1u64)).is_ok());
+    assert!(rsaep_octets(&public_key, &[0xff; 64]).is_err());
+}
+
+#[test]
+fn changed_key_fields_bypass_cached_contexts() {
+    let (first, second) = (generate_keypair(512), generate_keypair(512));
+    let mut public_key: RSAPublicKey = (&first).into();
+    let message = BigInt::from(42u64);
+
+    rsaep(&public_key, &message).unwrap();
+    public_key.modulus = second.modulus.clone();
+    let ciphertext = rsaep(&public_key, &message).unwrap();
+
+    assert_eq!(rsadp(&second, &ciphertext).unwrap(), message);
+}
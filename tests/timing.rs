@@ -0,0 +1,159 @@
+//! A dudect-style timing test, after Reparaz, Balasch and Verbauwhede,
+//! "Dude, is my code constant time?" (2017).
+//!
+//! The operation under test is timed on inputs from two classes, a fixed
+//! exponent and random exponents, in random interleaved order. If the run
+//! time does not depend on the exponent, the two timing distributions are
+//! the same and Welch's t-statistic stays small. Measurements above a
+//! percentile of all timings are cropped first, since interrupts and
+//! scheduling only ever add time.
+//!
+//! Timing is noisy, so both constant-time runs are ignored by default:
+//! `cargo test --release --test timing -- --ignored`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use naive_rsa::arith::MontgomeryContext;
+use num_bigint::{BigUint, RandBigInt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// |t| above this is taken as evidence of a timing leak, as in dudect.
+const THRESHOLD: f64 = 10.0;
+
+/// Keeps the fastest fraction of all measurements.
+const CROP_PERCENTILE: f64 = 0.9;
+
+/// Welch's t-statistic for the difference in means of two samples.
+fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len() as f64;
+    let variance = |xs: &[f64], m: f64| {
+        xs.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / (xs.len() - 1) as f64
+    };
+
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let (var_a, var_b) = (variance(a, mean_a), variance(b, mean_b));
+
+    (mean_a - mean_b) / (var_a / a.len() as f64 + var_b / b.len() as f64).sqrt()
+}
+
+/// Times `operation` on `samples` inputs drawn from the two classes and
+/// returns the t-statistic of the cropped measurements.
+fn measure<I, P, O>(samples: usize, mut prepare: P, mut operation: O) -> f64
+where
+    P: FnMut(&mut StdRng, bool) -> I,
+    O: FnMut(&I),
+{
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+
+    // Inputs are generated up front so only the operation is timed
+    let inputs: Vec<(bool, I)> = (0..samples)
+        .map(|_| {
+            let class = rng.gen::<bool>();
+            (class, prepare(&mut rng, class))
+        })
+        .collect();
+
+    let timings: Vec<(bool, f64)> = inputs
+        .iter()
+        .map(|(class, input)| {
+            let start = Instant::now();
+            operation(black_box(input));
+            (*class, start.elapsed().as_nanos() as f64)
+        })
+        .collect();
+
+    let mut sorted: Vec<f64> = timings.iter().map(|(_, time)| *time).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let cutoff = sorted[((sorted.len() as f64) * CROP_PERCENTILE) as usize];
+
+    let class = |wanted: bool| -> Vec<f64> {
+        timings
+            .iter()
+            .filter(|(class, time)| *class == wanted && *time <= cutoff)
+            .map(|(_, time)| *time)
+            .collect()
+    };
+
+    welch_t(&class(false), &class(true)).abs()
+}
+
+/// An odd 512-bit modulus, which is all the exponentiation cares about.
+fn modulus(rng: &mut StdRng) -> BigUint {
+    rng.gen_biguint(512) | (BigUint::from(1u64) << 511) | BigUint::from(1u64)
+}
+
+/// Fixed class: `2^(bits - 1) + 1`, a single window digit repeated. Random
+/// class: a random exponent with the top bit set. Both have the same length,
+/// since a `BigUint` does not hide how many limbs it has.
+fn exponent(rng: &mut StdRng, random: bool, bits: u64) -> BigUint {
+    let top = BigUint::from(1u64) << (bits - 1);
+    if random {
+        rng.gen_biguint(bits) | top
+    } else {
+        top | BigUint::from(1u64)
+    }
+}
+
+fn constant_time_t(samples: usize) -> f64 {
+    let mut rng = StdRng::seed_from_u64(1);
+    let modulus = modulus(&mut rng);
+    let context = MontgomeryContext::new(&modulus).unwrap();
+
+    measure(
+        samples,
+        |rng, class| (rng.gen_biguint_below(&modulus), exponent(rng, class, 512)),
+        |(base, exponent)| {
+            black_box(context.pow(base, exponent));
+        },
+    )
+}
+
+/// Exponent 1 against full-width exponents is a leak any timer can see, so
+/// this checks the harness itself.
+#[test]
+fn harness_detects_variable_time_modpow() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let modulus = modulus(&mut rng);
+
+    let t = measure(
+        2_000,
+        |rng, class| {
+            let exponent = if class {
+                rng.gen_biguint(512)
+            } else {
+                BigUint::from(1u64)
+            };
+            (rng.gen_biguint_below(&modulus), exponent)
+        },
+        |(base, exponent)| {
+            black_box(base.modpow(exponent, &modulus));
+        },
+    );
+
+    assert!(t > THRESHOLD, "t = {}", t);
+}
+
+/// Checks the statistic itself on fixed data, without timing anything.
+#[test]
+fn welch_t_matches_a_worked_example() {
+    // Means 3 and 6, variances 2.5 and 10: t = -3 / sqrt(0.5 + 2)
+    let t = welch_t(&[1.0, 2.0, 3.0, 4.0, 5.0], &[2.0, 4.0, 6.0, 8.0, 10.0]);
+    assert!((t + 3.0 / 2.5f64.sqrt()).abs() < 1e-12, "t = {}", t);
+
+    assert_eq!(welch_t(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]), 0.0);
+}
+
+#[test]
+#[ignore]
+fn montgomery_pow_is_constant_time_short() {
+    let t = constant_time_t(2_000);
+    assert!(t < THRESHOLD, "t = {}", t);
+}
+
+#[test]
+#[ignore]
+fn montgomery_pow_is_constant_time() {
+    let t = constant_time_t(100_000);
+    assert!(t < THRESHOLD, "t = {}", t);
+}